@@ -0,0 +1,132 @@
+use std::error::Error;
+use std::fmt;
+
+use num_bigint::BigInt;
+
+/// The result of solving one part of a day, whatever integer type that day produces.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    I32(i32),
+    U32(u32),
+    I64(i64),
+    BigInt(BigInt),
+}
+
+impl From<i32> for Answer {
+    fn from(value: i32) -> Self {
+        Answer::I32(value)
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(value: u32) -> Self {
+        Answer::U32(value)
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(value: i64) -> Self {
+        Answer::I64(value)
+    }
+}
+
+impl From<BigInt> for Answer {
+    fn from(value: BigInt) -> Self {
+        Answer::BigInt(value)
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::I32(value) => write!(f, "{}", value),
+            Answer::U32(value) => write!(f, "{}", value),
+            Answer::I64(value) => write!(f, "{}", value),
+            Answer::BigInt(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// Why a part could not find an answer even though its input parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SolveError(pub String);
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for SolveError {}
+
+/// What a part can return: any answer type, or a `Result` of one for parts that can fail.
+pub trait IntoAnswer {
+    fn into_answer(self) -> Result<Answer, SolveError>;
+}
+
+impl<T: Into<Answer>> IntoAnswer for T {
+    fn into_answer(self) -> Result<Answer, SolveError> {
+        Ok(self.into())
+    }
+}
+
+impl<T: Into<Answer>> IntoAnswer for Result<T, SolveError> {
+    fn into_answer(self) -> Result<Answer, SolveError> {
+        self.map(Into::into)
+    }
+}
+
+/// The answers produced by a single run of a day. Parts that were not run are `None`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Answers {
+    pub part1: Option<Answer>,
+    pub part2: Option<Answer>,
+    /// Extra lines a day wants shown with its answers, such as workings asked for by an option.
+    pub report: Vec<String>,
+}
+
+impl Answers {
+    pub fn get(&self, part: u32) -> Option<&Answer> {
+        match part {
+            1 => self.part1.as_ref(),
+            2 => self.part2.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_will_convert_each_answer_type() {
+        assert_eq!(Answer::from(-3), Answer::I32(-3));
+        assert_eq!(Answer::from(3u32), Answer::U32(3));
+        assert_eq!(Answer::from(3i64), Answer::I64(3));
+        assert_eq!(Answer::from(BigInt::from(3)), Answer::BigInt(BigInt::from(3)));
+    }
+
+    #[test]
+    fn it_will_display_answers_as_plain_numbers() {
+        assert_eq!(Answer::I32(-42).to_string(), "-42");
+        assert_eq!(Answer::I64(116094961956019).to_string(), "116094961956019");
+        assert_eq!(Answer::BigInt(BigInt::from(6461289671426i64)).to_string(), "6461289671426");
+    }
+
+    #[test]
+    fn it_will_pass_on_parts_that_fail() {
+        assert_eq!(3u32.into_answer(), Ok(Answer::U32(3)));
+        assert_eq!(Ok::<u32, SolveError>(3).into_answer(), Ok(Answer::U32(3)));
+        let error = SolveError(String::from("no order"));
+        assert_eq!(Err::<u32, SolveError>(error.clone()).into_answer(), Err(error));
+    }
+
+    #[test]
+    fn it_will_get_answers_by_part() {
+        let answers = Answers { part1: Some(Answer::I32(1)), part2: None, report: Vec::new() };
+        assert_eq!(answers.get(1), Some(&Answer::I32(1)));
+        assert_eq!(answers.get(2), None);
+        assert_eq!(answers.get(3), None);
+    }
+}
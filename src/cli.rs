@@ -0,0 +1,289 @@
+use std::path::PathBuf;
+
+use advent_of_code_2024::options::Options;
+use advent_of_code_2024::solution::Part;
+use log::LevelFilter;
+
+const DEFAULT_ANSWERS: &str = "src/inputs/answers.toml";
+
+pub const USAGE: &str = "\
+Usage: advent-of-code-2024 [COMMAND]
+
+Commands:
+    run --day <N> [--part <1|2>] [--variant <NAME> | --input <PATH>] [--option <KEY[=VALUE]>...] [--dot [PATH]]
+                                  Solve a single day without any prompts
+    verify [--answers <PATH>]     Check every day against the expected answers
+    all                           Run every day and print a timing table
+    help                          Print this message
+
+Options for any command:
+    --verbose                     Log what each day is doing
+    --trace                       Log every step as well, which is very noisy
+    --log-days <N,...>            Only log these days
+    --threads <N>                 Threads for parallel searches, defaulting to one per core
+
+Inputs are read from src/inputs/dayNN.txt, or src/inputs/dayNN.<NAME>.txt with --variant.
+Pass --input - to read the input from stdin.
+Some days take options, such as --option operators=+,*,- for day 7.
+--dot draws day 5's rules as a Graphviz graph, the same as --option dot[=PATH].
+Running without a command starts the interactive prompts.";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Interactive,
+    Help,
+    Run(RunArgs),
+    Verify(VerifyArgs),
+    All,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct RunArgs {
+    pub day: u32,
+    pub part: Part,
+    pub input: Option<PathBuf>,
+    pub variant: Option<String>,
+    pub options: Options,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct VerifyArgs {
+    pub answers: PathBuf,
+}
+
+/// Options that work with any command.
+#[derive(Debug, PartialEq, Eq)]
+pub struct GlobalOptions {
+    pub log_level: LevelFilter,
+    pub log_days: Vec<u32>,
+    /// `None` leaves it to the parallel module to use every core.
+    pub threads: Option<usize>,
+}
+
+/// Pulls the global options out of `args`, returning them with the remaining arguments.
+pub fn parse_global_options(args: &[String]) -> Result<(GlobalOptions, Vec<String>), String> {
+    let mut options = GlobalOptions { log_level: LevelFilter::Warn, log_days: Vec::new(), threads: None };
+    let mut rest = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--verbose" => options.log_level = options.log_level.max(LevelFilter::Debug),
+            "--trace" => options.log_level = LevelFilter::Trace,
+            "--log-days" => {
+                options.log_days = next_value(arg, &mut args)?
+                    .split(',')
+                    .map(parse_day)
+                    .collect::<Result<Vec<u32>, String>>()?;
+            }
+            "--threads" => options.threads = Some(parse_threads(next_value(arg, &mut args)?)?),
+            _ => rest.push(arg.clone()),
+        }
+    }
+    Ok((options, rest))
+}
+
+pub fn parse_args(args: &[String]) -> Result<Command, String> {
+    let Some((command, rest)) = args.split_first() else {
+        return Ok(Command::Interactive);
+    };
+    match command.as_str() {
+        "run" => parse_run(rest).map(Command::Run),
+        "verify" => parse_verify(rest).map(Command::Verify),
+        "all" => match rest.first() {
+            None => Ok(Command::All),
+            Some(other) => Err(format!("Unknown option '{}'", other)),
+        },
+        "help" | "--help" | "-h" => Ok(Command::Help),
+        other => Err(format!("Unknown command '{}'", other)),
+    }
+}
+
+fn parse_run(args: &[String]) -> Result<RunArgs, String> {
+    let mut day = None;
+    let mut part = Part::Both;
+    let mut input = None;
+    let mut variant = None;
+    let mut options = Options::default();
+    let mut args = args.iter().peekable();
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "--day" | "-d" => day = Some(parse_day(next_value(flag, &mut args)?)?),
+            "--part" | "-p" => part = parse_part(next_value(flag, &mut args)?)?,
+            "--input" | "-i" => input = Some(PathBuf::from(next_value(flag, &mut args)?)),
+            "--variant" | "-v" => variant = Some(next_value(flag, &mut args)?.to_string()),
+            "--option" | "-o" => options.insert(next_value(flag, &mut args)?),
+            "--dot" => match args.next_if(|value| !value.starts_with("--")) {
+                Some(path) => options.insert(&format!("dot={}", path)),
+                None => options.insert("dot"),
+            },
+            other => return Err(format!("Unknown option '{}'", other)),
+        }
+    }
+    if input.is_some() && variant.is_some() {
+        return Err(String::from("Options --input and --variant cannot be used together"));
+    }
+    Ok(RunArgs {
+        day: day.ok_or("Missing required option --day")?,
+        part,
+        input,
+        variant,
+        options,
+    })
+}
+
+fn parse_verify(args: &[String]) -> Result<VerifyArgs, String> {
+    let mut answers = PathBuf::from(DEFAULT_ANSWERS);
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "--answers" | "-a" => answers = PathBuf::from(next_value(flag, &mut args)?),
+            other => return Err(format!("Unknown option '{}'", other)),
+        }
+    }
+    Ok(VerifyArgs { answers })
+}
+
+fn next_value<'a>(flag: &str, args: &mut impl Iterator<Item = &'a String>) -> Result<&'a str, String> {
+    args.next()
+        .map(|value| value.as_str())
+        .ok_or_else(|| format!("Option {} expects a value", flag))
+}
+
+fn parse_day(value: &str) -> Result<u32, String> {
+    match value.parse::<u32>() {
+        Ok(day) if (1..=25).contains(&day) => Ok(day),
+        _ => Err(format!("Invalid day '{}', expected a number from 1 to 25", value)),
+    }
+}
+
+fn parse_threads(value: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(threads) if threads > 0 => Ok(threads),
+        _ => Err(format!("Invalid thread count '{}', expected a positive number", value)),
+    }
+}
+
+fn parse_part(value: &str) -> Result<Part, String> {
+    match value {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => Err(format!("Invalid part '{}', expected 1 or 2", value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn it_will_fall_back_to_interactive_without_arguments() {
+        assert_eq!(parse_args(&[]), Ok(Command::Interactive));
+    }
+
+    #[test]
+    fn it_will_parse_a_run_command() {
+        let command = parse_args(&args("run --day 7 --part 2 --input src/inputs/day07.txt"));
+        assert_eq!(command, Ok(Command::Run(RunArgs {
+            day: 7,
+            part: Part::Two,
+            input: Some(PathBuf::from("src/inputs/day07.txt")),
+            variant: None,
+            options: Options::default(),
+        })));
+    }
+
+    #[test]
+    fn it_will_run_both_parts_of_the_default_input_by_default() {
+        let command = parse_args(&args("run -d 1"));
+        assert_eq!(command, Ok(Command::Run(RunArgs {
+            day: 1,
+            part: Part::Both,
+            input: None,
+            variant: None,
+            options: Options::default(),
+        })));
+    }
+
+    #[test]
+    fn it_will_parse_an_input_variant() {
+        let command = parse_args(&args("run --day 6 --variant example"));
+        assert_eq!(command, Ok(Command::Run(RunArgs {
+            day: 6,
+            part: Part::Both,
+            input: None,
+            variant: Some(String::from("example")),
+            options: Options::default(),
+        })));
+    }
+
+    #[test]
+    fn it_will_collect_day_options() {
+        let Ok(Command::Run(run)) = parse_args(&args("run --day 7 --option operators=+,- -o explain")) else {
+            panic!("expected a run command");
+        };
+        assert_eq!(run.options.get("operators"), Ok(Some("+,-")));
+        assert!(run.options.is_set("explain"));
+        assert!(parse_args(&args("run --day 7 --option")).is_err());
+    }
+
+    #[test]
+    fn it_will_take_dot_as_a_day_option() {
+        let Ok(Command::Run(run)) = parse_args(&args("run --day 5 --dot --part 1")) else {
+            panic!("expected a run command");
+        };
+        assert_eq!(run.options.flag("dot"), Some(None));
+        assert_eq!(run.part, Part::One);
+
+        let Ok(Command::Run(run)) = parse_args(&args("run --day 5 --dot rules.dot")) else {
+            panic!("expected a run command");
+        };
+        assert_eq!(run.options.flag("dot"), Some(Some("rules.dot")));
+    }
+
+    #[test]
+    fn it_will_parse_a_verify_command() {
+        assert_eq!(parse_args(&args("verify")), Ok(Command::Verify(VerifyArgs { answers: PathBuf::from(DEFAULT_ANSWERS) })));
+        assert_eq!(parse_args(&args("verify --answers mine.toml")), Ok(Command::Verify(VerifyArgs { answers: PathBuf::from("mine.toml") })));
+    }
+
+    #[test]
+    fn it_will_parse_an_all_command() {
+        assert_eq!(parse_args(&args("all")), Ok(Command::All));
+    }
+
+    #[test]
+    fn it_will_pull_out_global_options() {
+        let (options, rest) = parse_global_options(&args("run --day 6 --verbose --log-days 6,9 --threads 4")).unwrap();
+        assert_eq!(options, GlobalOptions { log_level: LevelFilter::Debug, log_days: vec![6, 9], threads: Some(4) });
+        assert_eq!(rest, args("run --day 6"));
+
+        let (options, rest) = parse_global_options(&args("--trace --verbose")).unwrap();
+        assert_eq!(options, GlobalOptions { log_level: LevelFilter::Trace, log_days: Vec::new(), threads: None });
+        assert!(rest.is_empty());
+
+        let (options, _) = parse_global_options(&args("all")).unwrap();
+        assert_eq!(options.log_level, LevelFilter::Warn);
+
+        assert!(parse_global_options(&args("all --log-days 6,x")).is_err());
+        assert!(parse_global_options(&args("all --log-days")).is_err());
+        assert!(parse_global_options(&args("all --threads 0")).is_err());
+        assert!(parse_global_options(&args("all --threads many")).is_err());
+    }
+
+    #[test]
+    fn it_will_reject_bad_arguments() {
+        assert!(parse_args(&args("run --input input.txt")).is_err());
+        assert!(parse_args(&args("run --day 6 --input input.txt --variant example")).is_err());
+        assert!(parse_args(&args("run --day 0 --input input.txt")).is_err());
+        assert!(parse_args(&args("run --day 1 --part 3 --input input.txt")).is_err());
+        assert!(parse_args(&args("run --day 1 --input")).is_err());
+        assert!(parse_args(&args("run --day 1 --input input.txt --bogus")).is_err());
+        assert!(parse_args(&args("verify --answers")).is_err());
+        assert!(parse_args(&args("all --day 1")).is_err());
+        assert!(parse_args(&args("launch")).is_err());
+    }
+}
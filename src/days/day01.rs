@@ -1,42 +1,53 @@
-use std::io::{self, BufRead};
-use std::fs::File;
+use crate::parse::{self, ParseError};
+use crate::solution::Solution;
 
-pub fn solve(input: File) {
-    let formatted = format_input(input);
+pub struct Day01;
 
-    let part1 = solve_part1(formatted.clone());
-    println!("The answer for part 1 is: {}", part1);
+impl Solution for Day01 {
+    const DAY: u32 = 1;
+    const TITLE: &'static str = "Historian Hysteria";
 
-    let part2 = solve_part2(formatted.clone());
-    println!("The answer for part 2 is: {}", part2);
+    type Input = Input;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        format_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        solve_part1(input.clone())
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        solve_part2(input.clone())
+    }
 }
 
 fn solve_part1(input: Input) -> i32 {
     let sorted = sort_input(input);
     let subtracted = subtract_lists(sorted.first, sorted.second);
-    let sum = sum_list(subtracted);
-    sum
+    
+    sum_list(subtracted)
 }
 
 fn solve_part2(input: Input) -> i32 {
     let similarity = calculate_similarity(input.first, input.second);
-    let sum = sum_list(similarity);
-    sum
+    
+    sum_list(similarity)
 }
 
-fn format_input(input: File) -> Input {
+fn format_input(input: &str) -> Result<Input, ParseError> {
     let mut parsed_input = Input {
         first: Vec::new(),
         second: Vec::new()
     };
-    let reader = io::BufReader::new(input);
-    for line in reader.lines() {
-        let line = line.unwrap();
-        let split = line.split("   ").collect::<Vec<&str>>();
-        parsed_input.first.push(split[0].parse::<i32>().unwrap());
-        parsed_input.second.push(split[1].parse::<i32>().unwrap());
+    for line in parse::lines(input) {
+        let (first, second) = line.split_once("   ", "two numbers separated by three spaces")?;
+        parsed_input.first.push(line.parse(first, "a number")?);
+        parsed_input.second.push(line.parse(second, "a number")?);
     }
-    parsed_input
+    Ok(parsed_input)
 }
 
 fn sort_input(input: Input) -> Input {
@@ -60,20 +71,20 @@ fn sum_list(list: Vec<i32>) -> i32 {
 
 fn calculate_similarity(first: Vec<i32>, second: Vec<i32>) -> Vec<i32> {
     let mut similarity = Vec::new();
-    for i in 0..first.len() {
+    for left in &first {
         let mut count = 0;
-        for j in 0..second.len() {
-            if first[i] == second[j] {
+        for right in &second {
+            if left == right {
                 count += 1;
             }
         }
-        similarity.push(first[i] * count);
+        similarity.push(left * count);
     }
     similarity
 }
 
-#[derive(Clone)]
-struct Input {
+#[derive(Clone, Debug)]
+pub struct Input {
     first: Vec<i32>,
     second: Vec<i32>
 }
@@ -81,18 +92,11 @@ struct Input {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::io::Write;
-    use tempfile::NamedTempFile;
 
     #[test]
     fn it_will_solve_the_problem_for_part_1() {
-        let mut temp_file = NamedTempFile::new().unwrap();
-        writeln!(temp_file, "1000   4000").unwrap();
-        writeln!(temp_file, "2000   5000").unwrap();
-        writeln!(temp_file, "3000   6000").unwrap();
-
-        let input = temp_file.reopen().unwrap();
-        let formatted = format_input(input);
+        let input = "1000   4000\n2000   5000\n3000   6000\n";
+        let formatted = format_input(input).unwrap();
 
         let answer = solve_part1(formatted);
         assert_eq!(answer, 9000);
@@ -100,30 +104,29 @@ mod tests {
 
     #[test]
     fn it_will_solve_the_problem_for_part_2() {
-        let mut temp_file = NamedTempFile::new().unwrap();
-        writeln!(temp_file, "1234   4000").unwrap();
-        writeln!(temp_file, "4000   1234").unwrap();
-        writeln!(temp_file, "3000   1234").unwrap();
-
-        let input = temp_file.reopen().unwrap();
-        let formatted = format_input(input);
+        let input = "1234   4000\n4000   1234\n3000   1234\n";
+        let formatted = format_input(input).unwrap();
 
         let answer = solve_part2(formatted);
-        assert_eq!(answer, (1234 * 2) + (4000 * 1));
+        assert_eq!(answer, (1234 * 2) + 4000);
     }
 
     #[test]
     fn it_will_format_the_input_file_into_two_lists() {
-        let mut temp_file = NamedTempFile::new().unwrap();
-        writeln!(temp_file, "1000   4000").unwrap();
-        writeln!(temp_file, "2000   5000").unwrap();
-        writeln!(temp_file, "3000   6000").unwrap();
+        let input = "1000   4000\n2000   5000\n3000   6000\n";
 
-        let formatted_input = format_input(temp_file.reopen().unwrap());
+        let formatted_input = format_input(input).unwrap();
         assert_eq!(formatted_input.first, vec![1000, 2000, 3000]);
         assert_eq!(formatted_input.second, vec![4000, 5000, 6000]);
+    }
+
+    #[test]
+    fn it_will_report_malformed_lines() {
+        let error = format_input("1   2\n3 4\n").err().unwrap();
+        assert_eq!((error.line, error.column), (2, 4));
 
-        temp_file.close().unwrap();
+        let error = format_input("1   2\n3   x\n").err().unwrap();
+        assert_eq!((error.line, error.column, error.expected.as_str()), (2, 5, "a number"));
     }
 
     #[test]
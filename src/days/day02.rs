@@ -1,17 +1,32 @@
-use std::io::{self, BufRead};
-use std::fs::File;
+use log::trace;
 
-pub fn solve(input: File) {
-    let reports = format_input(input);
+use crate::parse::{self, ParseError};
+use crate::solution::Solution;
 
-    let part1 = solve_part1(&reports);
-    println!("The answer for part 1 is: {}", part1);
+pub struct Day02;
 
-    let part2 = solve_part2(&reports);
-    println!("The answer for part 2 is: {}", part2);
+impl Solution for Day02 {
+    const DAY: u32 = 2;
+    const TITLE: &'static str = "Red-Nosed Reports";
+
+    type Input = Vec<Report>;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        format_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        solve_part2(input)
+    }
 }
 
-fn solve_part1(input: &Vec<Report>) -> i32 {
+fn solve_part1(input: &[Report]) -> i32 {
     let mut count = 0;
     for report in input {
         if is_safe(calc_changes(report)) {
@@ -21,7 +36,7 @@ fn solve_part1(input: &Vec<Report>) -> i32 {
     count
 }
 
-fn solve_part2(input: &Vec<Report>) -> i32 {
+fn solve_part2(input: &[Report]) -> i32 {
     let mut count = 0;
     let unsafe_reports = input.iter().filter(|report| !is_safe(calc_changes(report)));
     for report in unsafe_reports {
@@ -33,26 +48,22 @@ fn solve_part2(input: &Vec<Report>) -> i32 {
     count
 }
 
-fn format_input(input: File) -> Vec<Report> {
+fn format_input(input: &str) -> Result<Vec<Report>, ParseError> {
     let mut reports = Vec::new();
-    let reader = io::BufReader::new(input);
-    let content: String = reader.lines()
-        .map(|line| line.unwrap())
-        .collect::<Vec<String>>()
-        .join("\n");
-    
-    println!("File contents:\n{}", content);
-
-    // Now recreate the reader since we consumed it
-    let reader = io::BufReader::new(content.as_bytes());
-    for line in reader.lines() {
-        reports.push(Report { levels: format_line(line.unwrap()) });
+    trace!("File contents:\n{}", input);
+
+    for line in parse::lines(input) {
+        reports.push(Report { levels: format_line(line)? });
     }
-    reports
+    Ok(reports)
 }
 
-fn format_line(line: String) -> Vec<i32> {
-    line.split_whitespace().map(|s| s.parse().unwrap()).collect()
+fn format_line(line: parse::Line) -> Result<Vec<i32>, ParseError> {
+    let levels = line.text.split_whitespace().map(|s| line.parse(s, "a level")).collect::<Result<Vec<i32>, ParseError>>()?;
+    if levels.is_empty() {
+        return Err(line.error_at_end("at least one level"));
+    }
+    Ok(levels)
 }
 
 fn is_safe(changes: Vec<i32>) -> bool {
@@ -74,51 +85,37 @@ fn is_safe_dampened(report: &Report) -> bool {
 }
 
 fn calc_changes(report: &Report) -> Vec<i32> {
-    let mut changes = Vec::new();
-    for i in 0..report.levels.len() - 1 {
-        changes.push(report.levels[i + 1] - report.levels[i]);
-    }
-    changes
+    report.levels.windows(2).map(|pair| pair[1] - pair[0]).collect()
 }
 
 #[derive(Clone)]
-struct Report {
+pub struct Report {
     levels: Vec<i32>
 }
 
 #[cfg(test)]
 mod tests {
-    use std::io::Write;
-    use tempfile::NamedTempFile;
-
     use super::*;
 
+    const EXAMPLE: &str = "\
+7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9
+";
+
     #[test]
     fn it_will_solve_part_1() {
-        let mut temp_file = NamedTempFile::new().unwrap();
-        writeln!(temp_file, "7 6 4 2 1").unwrap();
-        writeln!(temp_file, "1 2 7 8 9").unwrap();
-        writeln!(temp_file, "9 7 6 2 1").unwrap();
-        writeln!(temp_file, "1 3 2 4 5").unwrap();
-        writeln!(temp_file, "8 6 4 4 1").unwrap();
-        writeln!(temp_file, "1 3 6 7 9").unwrap();
-
-        let input = format_input(temp_file.reopen().unwrap());
+        let input = format_input(EXAMPLE).unwrap();
         let result = solve_part1(&input);
         assert_eq!(result, 2);
     }
 
     #[test]
     fn it_will_solve_part_2() {
-        let mut temp_file = NamedTempFile::new().unwrap();
-        writeln!(temp_file, "7 6 4 2 1").unwrap();
-        writeln!(temp_file, "1 2 7 8 9").unwrap();
-        writeln!(temp_file, "9 7 6 2 1").unwrap();
-        writeln!(temp_file, "1 3 2 4 5").unwrap();
-        writeln!(temp_file, "8 6 4 4 1").unwrap();
-        writeln!(temp_file, "1 3 6 7 9").unwrap();
-
-        let input =format_input(temp_file.reopen().unwrap());
+        let input = format_input(EXAMPLE).unwrap();
 
         let result = solve_part2(&input);
         assert_eq!(result, 4);
@@ -126,15 +123,15 @@ mod tests {
 
     #[test]
     fn it_will_format_the_input_correctly() {
-        let mut temp_file = NamedTempFile::new().unwrap();
-        writeln!(temp_file, "10 9 7 6 3").unwrap();
-        writeln!(temp_file, "4 5 6 7 8").unwrap();
-        writeln!(temp_file, "10 9 7 6 1").unwrap();
-        writeln!(temp_file, "4 10 11 12 13").unwrap();
-        writeln!(temp_file, "1 2 3 3 5").unwrap();
-        writeln!(temp_file, "1 2 3 2 1").unwrap();
-
-        let result = format_input(temp_file.reopen().unwrap());
+        let input = "\
+            10 9 7 6 3\n\
+            4 5 6 7 8\n\
+            10 9 7 6 1\n\
+            4 10 11 12 13\n\
+            1 2 3 3 5\n\
+            1 2 3 2 1\n";
+
+        let result = format_input(input).unwrap();
         assert_eq!(result.len(), 6);
         assert_eq!(result[0].levels, vec![10, 9, 7, 6, 3]);
         assert_eq!(result[1].levels, vec![4, 5, 6, 7, 8]);
@@ -144,12 +141,21 @@ mod tests {
         assert_eq!(result[5].levels, vec![1, 2, 3, 2, 1]);
     }
 
+    #[test]
+    fn it_will_report_a_level_that_is_not_a_number() {
+        let error = format_input("1 2 3\n4 five 6\n").err().unwrap();
+        assert_eq!((error.line, error.column), (2, 3));
+
+        let error = format_input("1 2 3\n\n4 5 6\n").err().unwrap();
+        assert_eq!((error.line, error.column), (2, 1));
+    }
+
     #[test]
     fn it_will_determine_if_a_report_is_safe_based_on_changes() {
-        assert_eq!(is_safe(vec![-1, -2, -1, -3]), true);
-        assert_eq!(is_safe(vec![-1, -2, 1, 3]), false);
-        assert_eq!(is_safe(vec![3, 2, 1, 3]), true);
-        assert_eq!(is_safe(vec![1, 10, 1, 3]), false);
+        assert!(is_safe(vec![-1, -2, -1, -3]));
+        assert!(!is_safe(vec![-1, -2, 1, 3]));
+        assert!(is_safe(vec![3, 2, 1, 3]));
+        assert!(!is_safe(vec![1, 10, 1, 3]));
     }
 
     #[test]
@@ -161,12 +167,12 @@ mod tests {
     #[test]
     fn it_will_ignore_one_unsafe_change() {
         let mut report = Report { levels: vec![9, 8, 6, 7, 4] };
-        assert_eq!(is_safe_dampened(&report), true);
+        assert!(is_safe_dampened(&report));
 
         report = Report {levels: vec![11, 10, 8, 9, 4] };
-        assert_eq!(is_safe_dampened(&report), false);
+        assert!(!is_safe_dampened(&report));
 
         report = Report {levels: vec![2, 3, 1, 2, 5]};
-        assert_eq!(is_safe_dampened(&report), false);
+        assert!(!is_safe_dampened(&report));
     }
 }
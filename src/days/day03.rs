@@ -1,18 +1,32 @@
-use std::io::{self, BufRead};
-use std::fs::File;
 use regex::Regex;
 
-pub fn solve(input: File) {
-    let input_string = format_input(input);
+use crate::parse::ParseError;
+use crate::solution::Solution;
 
-    let part1 = solve_part1(&input_string);
-    println!("The answer for part 1 is: {}", part1);
+pub struct Day03;
 
-    let part2 = solve_part2(&input_string);
-    println!("The answer for part 2 is: {}", part2);
+impl Solution for Day03 {
+    const DAY: u32 = 3;
+    const TITLE: &'static str = "Mull It Over";
+
+    type Input = String;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok(format_input(input))
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        solve_part2(input)
+    }
 }
 
-fn solve_part1(input: &String) -> i32 {
+fn solve_part1(input: &str) -> i32 {
     let muls = search_for_mul(input);
     let parsed_muls = parse_mul(muls);
     let mut result = 0;
@@ -22,7 +36,7 @@ fn solve_part1(input: &String) -> i32 {
     result
 }
 
-fn solve_part2(input: &String) -> i32 {
+fn solve_part2(input: &str) -> i32 {
     let mut ans = solve_part1(input);
     let donts = parse_donts(input);
     for dont in donts {
@@ -31,17 +45,14 @@ fn solve_part2(input: &String) -> i32 {
     ans
 }
 
-fn format_input(input: File) -> String {
-    let reader = io::BufReader::new(input);
-    let content: String = reader.lines()
-        .map(|line| line.unwrap())
-        .collect::<Vec<String>>()
-        .join("");
-    content
+fn format_input(input: &str) -> String {
+    input.lines().collect::<Vec<&str>>().join("")
 }
 
-fn search_for_mul(input: &String) -> Vec<String> {
-    let re = Regex::new(r"mul\([0-9]+,[0-9]+\)").unwrap();
+/// Finds each `mul(X,Y)` instruction. The numbers have one to three digits, so anything
+/// longer is corrupted rather than an instruction.
+fn search_for_mul(input: &str) -> Vec<String> {
+    let re = Regex::new(r"mul\([0-9]{1,3},[0-9]{1,3}\)").unwrap();
     let matches = re.find_iter(input);
     let mut result = Vec::new();
     for m in matches {
@@ -54,19 +65,19 @@ fn parse_mul(mul_strs: Vec<String>) -> Vec<Mul> {
     let mut result = Vec::new();
     for str in mul_strs {
         let num_str = str.replace("mul(", "").replace(")", "");
-        let nums = num_str.split(",").map(|s| s.parse::<i32>().unwrap()).collect::<Vec<i32>>();
-        result.push(Mul { a: nums[0], b: nums[1] });
+        let Some((Ok(a), Ok(b))) = num_str.split_once(",").map(|(a, b)| (a.parse(), b.parse())) else { continue };
+        result.push(Mul { a, b });
     }
     result
 }
 
-fn parse_donts(input: &String) -> Vec<Mul> {
+fn parse_donts(input: &str) -> Vec<Mul> {
     let re = Regex::new(r"don't\(\)([\s\S]*?)(?:do\(\)|$)").unwrap();
     let matches = re.find_iter(input);
     let mut result = Vec::new();
     for m in matches {
-        let donts = search_for_mul(&m.as_str().to_string());
-        result.extend(parse_mul(donts).into_iter());
+        let donts = search_for_mul(m.as_str());
+        result.extend(parse_mul(donts));
     }
     result
 }
@@ -79,15 +90,11 @@ struct Mul {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::io::Write;
-    use tempfile::NamedTempFile;
 
     #[test]
     fn it_will_format_the_input_file() {
-        let mut temp_file = NamedTempFile::new().unwrap();
         let expected = "lorem ipsum dolor sit amet";
-        temp_file.write_all(expected.as_bytes()).unwrap();
-        let actual = format_input(temp_file.reopen().unwrap());
+        let actual = format_input("lorem ipsum \ndolor sit amet\n");
         assert_eq!(expected, actual);
     }
 
@@ -96,6 +103,9 @@ mod tests {
         let input = String::from("lorem ipsum dolor sit ametmul(1,2)+mul[3,4]mul(5,6)mul(7, 8)");
         let result = search_for_mul(&input);
         assert_eq!(result, vec!["mul(1,2)","mul(5,6)"]);
+
+        let result = search_for_mul("mul(99999999999,2)mul(999,999)");
+        assert_eq!(result, vec!["mul(999,999)"]);
     }
 
     #[test]
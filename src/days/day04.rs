@@ -1,296 +1,98 @@
-use std::io::{self, BufRead};
-use std::fs::File;
-use regex::Regex;
+use crate::grid::Grid;
+use crate::parse::ParseError;
+use crate::search::{find_word, Template};
+use crate::solution::Solution;
 
-pub fn solve(input: File) {
-    let formatted_input = format_input(input);
+pub struct Day04;
 
-    let part1 = solve_part1(&formatted_input);
-    println!("The answer for part 1 is: {}", part1);
+impl Solution for Day04 {
+    const DAY: u32 = 4;
+    const TITLE: &'static str = "Ceres Search";
 
-    let part2 = solve_part2(&formatted_input);
-    println!("The answer for part 2 is: {}", part2);
-}
-
-fn solve_part1(input: &Vec<Vec<String>>) -> i32 {
-    let lines = get_lines(input);
-    let mega_string = get_mega_string(lines);
-    search_for_xmas(mega_string)
-}
-
-fn solve_part2(input: &Vec<Vec<String>>) -> i32 {
-    find_crossed_mas(input)
-}
+    type Input = Grid<char>;
+    type Output1 = i32;
+    type Output2 = i32;
 
-fn format_input(input: File) -> Vec<Vec<String>> {
-    let reader = io::BufReader::new(input);
-    let content: Vec<Vec<String>> = reader.lines()
-        .map(|line| {
-            line
-                .unwrap()
-                .chars()
-                .map(|c| c.to_string())
-                .collect::<Vec<String>>()
-        })
-        .collect::<Vec<Vec<String>>>();
-    content
-}
-
-fn find_crossed_mas(matrix: &Vec<Vec<String>>) -> i32 {
-    let mut count = 0;
-    for i in 1..matrix.len()-1 {
-        for j in 1..matrix[i].len()-1 {
-            if matrix[i][j] == "A" && check_adjacents(matrix, i, j) {
-                count += 1;
-            }
-        }
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        format_input(input)
     }
-    count
-}
-
-fn check_adjacents(matrix: &Vec<Vec<String>>, i: usize, j: usize) -> bool {
-    let dr = matrix[i - 1][j - 1].clone() + &matrix[i + 1][j + 1];
-    let dl = matrix[i - 1][j + 1].clone() + &matrix[i + 1][j - 1];
-    (dr == "MS" && (dl == "MS" || dl == "SM")) || (dr == "SM" && (dl == "MS" || dl == "SM"))
-}
 
-fn get_mega_string(lines: Vec<String>) -> String {
-    lines.join("|")
-}
-
-fn get_lines(matrix: &Vec<Vec<String>>) -> Vec<String> {
-    let mut lines = Vec::new();
-    lines.extend(get_horizontal_lines(&matrix));
-    lines.extend(get_vertical_lines(&matrix));
-    lines.extend(get_down_right_diagonal_lines(&matrix));
-    lines.extend(get_down_left_diagonal_lines(&matrix));
-    lines
-}
-
-fn search_for_xmas(mega_string: String) -> i32 {
-    let re = Regex::new(r"XMAS").unwrap();
-    let revre = Regex::new(r"SAMX").unwrap();
-    let matches = re.find_iter(&mega_string);
-    let revmatches = revre.find_iter(&mega_string);
-    let mut count = 0;
-    for _m in matches {
-        count += 1;
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        solve_part1(input)
     }
-    for _m in revmatches {
-        count += 1;
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        solve_part2(input)
     }
-    count
 }
 
-fn get_horizontal_lines(matrix: &Vec<Vec<String>>) -> Vec<String> {
-    let mut lines = Vec::new();
-    for i in 0..matrix.len() {
-        lines.push(matrix[i].join(""));
-    }
-    lines
+fn solve_part1(input: &Grid<char>) -> i32 {
+    find_word(input, "XMAS").len() as i32
 }
 
-fn get_vertical_lines(matrix: &Vec<Vec<String>>) -> Vec<String> {
-    let mut lines = Vec::new();
-    for j in 0..matrix[0].len() {
-        let mut vertical = Vec::new();
-        for i in 0..matrix.len() {
-            vertical.push(matrix[i][j].clone());
-        }
-        lines.push(vertical.join(""));
-    }
-    lines
+fn solve_part2(input: &Grid<char>) -> i32 {
+    find_crossed_mas(input)
 }
 
-fn get_down_right_diagonal_lines(matrix: &Vec<Vec<String>>) -> Vec<String> {
-    let mut lines = Vec::new();
-    let max_height = matrix[0].len();
-    let max_width = matrix.len();
-    let mut edge = Vec::new();
-    for i in (0..max_height).rev() {
-        edge.push((i, 0));
-    }
-    for j in 1..max_width {
-        edge.push((0, j));
-    }
-    for pos in edge { // 2,0 - 1,0 - 0,0 - 0,1 - 0,2
-        let mut diagonal = Vec::new();
-        let mut x = pos.0;
-        let mut y = pos.1;
-        loop {
-            diagonal.push(matrix[x][y].clone());
-            x += 1;
-            y += 1;
-            if x >= max_height || y >= max_width {
-                break;
-            }
-        }
-        lines.push(diagonal.join(""));
-    }
-    lines
+fn format_input(input: &str) -> Result<Grid<char>, ParseError> {
+    Grid::parse(input, Some, "a letter")
 }
 
-fn get_down_left_diagonal_lines(matrix: &Vec<Vec<String>>) -> Vec<String> {
-    let mut lines = Vec::new();
-    let max_height = matrix[0].len();
-    let max_width = matrix.len();
-    let mut edge = Vec::new();
-    for i in 0..max_width {
-        edge.push((0, i));
-    }
-    for j in 1..max_height {
-        edge.push((j, max_width - 1));
-    }
-    for pos in edge { // 0,0 - 0,1 - 0,2 - 1,2 - 2,2
-        let mut diagonal = Vec::new();
-        let mut x = pos.0;
-        let mut y = pos.1;
-        loop {
-            diagonal.push(matrix[x][y].clone());
-            x += 1;
-            if y <= 0 { break }
-            y -= 1;
-            if x >= max_height { break }
-        }
-        lines.push(diagonal.join(""));
-    }
-    lines
+/// Counts the X-MAS shapes: two diagonal MAS crossing at their A, in any of the four
+/// turns of the template.
+fn find_crossed_mas(grid: &Grid<char>) -> i32 {
+    let crossed_mas = Template::parse("M.S\n.A.\nM.S\n").expect("the X-MAS template is valid");
+    crossed_mas.rotations().iter().map(|template| template.find(grid).len() as i32).sum()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::{io::Write, vec};
-    use tempfile::NamedTempFile;
+
+    const EXAMPLE: &str = "\
+MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX
+";
 
     #[test]
     fn it_will_solve_part1() {
-        let mut temp_file = NamedTempFile::new().unwrap();
-        writeln!(temp_file, "MMMSXXMASM").unwrap();
-        writeln!(temp_file, "MSAMXMSMSA").unwrap();
-        writeln!(temp_file, "AMXSXMAAMM").unwrap();
-        writeln!(temp_file, "MSAMASMSMX").unwrap();
-        writeln!(temp_file, "XMASAMXAMM").unwrap();
-        writeln!(temp_file, "XXAMMXXAMA").unwrap();
-        writeln!(temp_file, "SMSMSASXSS").unwrap();
-        writeln!(temp_file, "SAXAMASAAA").unwrap();
-        writeln!(temp_file, "MAMMMXMMMM").unwrap();
-        writeln!(temp_file, "MXMXAXMASX").unwrap();
-        let input = format_input(temp_file.reopen().unwrap());
+        let input = format_input(EXAMPLE).unwrap();
         assert_eq!(solve_part1(&input), 18);
     }
 
     #[test]
     fn it_will_solve_part2() {
-        let mut temp_file = NamedTempFile::new().unwrap();
-        writeln!(temp_file, "MMMSXXMASM").unwrap();
-        writeln!(temp_file, "MSAMXMSMSA").unwrap();
-        writeln!(temp_file, "AMXSXMAAMM").unwrap();
-        writeln!(temp_file, "MSAMASMSMX").unwrap();
-        writeln!(temp_file, "XMASAMXAMM").unwrap();
-        writeln!(temp_file, "XXAMMXXAMA").unwrap();
-        writeln!(temp_file, "SMSMSASXSS").unwrap();
-        writeln!(temp_file, "SAXAMASAAA").unwrap();
-        writeln!(temp_file, "MAMMMXMMMM").unwrap();
-        writeln!(temp_file, "MXMXAXMASX").unwrap();
-        let input = format_input(temp_file.reopen().unwrap());
+        let input = format_input(EXAMPLE).unwrap();
         assert_eq!(solve_part2(&input), 9);
     }
 
     #[test]
-    fn it_will_search_for_xmas() {
-        let input = String::from("asdXMASasdfXMASAMXasdfXMAS");
-        assert_eq!(search_for_xmas(input), 4);
+    fn it_will_find_xmas_in_every_direction() {
+        let input = format_input("XMAS\nMMAA\nAXAS\nSAMX\n").unwrap();
+        let starts: Vec<_> = find_word(&input, "XMAS").into_iter().map(|found| (found.start, found.step)).collect();
+        assert_eq!(starts, vec![((0, 0), (0, 1)), ((0, 0), (1, 0)), ((3, 3), (0, -1))]);
     }
 
     #[test]
     fn it_will_take_the_string_input_and_make_it_a_matrix() {
-        let mut temp_file = NamedTempFile::new().unwrap();
-        writeln!(temp_file, "abc").unwrap();
-        writeln!(temp_file, "def").unwrap();
-        let formatted_input = format_input(temp_file.reopen().unwrap());
-        assert_eq!(formatted_input, vec![vec!["a", "b", "c"], vec!["d", "e", "f"]]);
-    }
-
-    #[test]
-    fn it_will_turn_the_matrix_into_lines() {
-        let input = vec![
-            vec![String::from("a"), String::from("b"), String::from("c")],
-            vec![String::from("d"), String::from("e"), String::from("f")],
-            vec![String::from("g"), String::from("h"), String::from("i")]
-        ];
-        let lines = get_lines(&input);
-        assert_eq!(lines.len(), 16);
-        assert_eq!(lines, vec![
-            "abc", "def", "ghi", // horizontal
-            "adg", "beh", "cfi", // vertical
-            "g", "dh", "aei", "bf", "c", // diag down-right
-            "a", "bd", "ceg", "fh", "i" // diag down-left
-        ]);
-    }
-
-    #[test]
-    fn it_will_turn_the_matrix_into_horizontal_lines() {
-        let input = vec![
-            vec![String::from("a"), String::from("b"), String::from("c")],
-            vec![String::from("d"), String::from("e"), String::from("f")],
-            vec![String::from("g"), String::from("h"), String::from("i")]
-        ];
-        let lines = get_horizontal_lines(&input);
-        assert_eq!(lines, vec!["abc", "def", "ghi"]);
-    }
-
-    #[test]
-    fn it_will_turn_the_matrix_into_vertical_lines() {
-        let input = vec![
-            vec![String::from("a"), String::from("b"), String::from("c")],
-            vec![String::from("d"), String::from("e"), String::from("f")],
-            vec![String::from("g"), String::from("h"), String::from("i")]
-        ];
-        let lines = get_vertical_lines(&input);
-        assert_eq!(lines, vec!["adg", "beh", "cfi"]);
-    }
-
-    #[test]
-    fn it_will_turn_the_matrix_into_diagonal_lines_down_right() {
-        let input = vec![
-            vec![String::from("a"), String::from("b"), String::from("c")],
-            vec![String::from("d"), String::from("e"), String::from("f")],
-            vec![String::from("g"), String::from("h"), String::from("i")]
-        ];
-        let lines = get_down_right_diagonal_lines(&input);
-        assert_eq!(lines, vec!["g", "dh", "aei", "bf", "c"]);
-    }
-
-    #[test]
-    fn it_will_turn_the_matrix_into_diagonal_lines_down_left() {
-        let input = vec![
-            vec![String::from("a"), String::from("b"), String::from("c")],
-            vec![String::from("d"), String::from("e"), String::from("f")],
-            vec![String::from("g"), String::from("h"), String::from("i")]
-        ];
-        let lines = get_down_left_diagonal_lines(&input);
-        assert_eq!(lines, vec!["a", "bd", "ceg", "fh", "i"]);
-    }
-
-    #[test]
-    fn it_will_get_the_mega_string() {
-        let input = vec![
-            vec![String::from("a"), String::from("b"), String::from("c")],
-            vec![String::from("d"), String::from("e"), String::from("f")],
-            vec![String::from("g"), String::from("h"), String::from("i")]
-        ];
-        let lines = get_mega_string(get_lines(&input));
-        assert_eq!(lines, "abc|def|ghi|adg|beh|cfi|g|dh|aei|bf|c|a|bd|ceg|fh|i");
+        let input = "abc\ndef\n";
+        let formatted_input = format_input(input).unwrap();
+        assert_eq!((formatted_input.width(), formatted_input.height()), (3, 2));
+        assert_eq!(formatted_input[(1, 0)], 'd');
+        assert_eq!(formatted_input.to_string(), input);
     }
 
     #[test]
-    fn it_will_check_adjacents() {
-        let input = vec![
-            vec![String::from("a"), String::from("b"), String::from("c")],
-            vec![String::from("d"), String::from("e"), String::from("f")],
-            vec![String::from("g"), String::from("h"), String::from("i")]
-        ];
+    fn it_will_find_crossed_mas() {
+        let input = format_input("MbS\ndAf\nMhS\n").unwrap();
         assert_eq!(find_crossed_mas(&input), 1);
     }
 }
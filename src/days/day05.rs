@@ -1,41 +1,94 @@
-use std::io::{self, BufRead};
-use std::fs::File;
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
 
-pub fn solve(input: File) {
-    let formatted_input = format_input(input);
-    let parsed_input = parse_raw_input(&formatted_input);
-    let results = check_all_updates(&parsed_input.page_rules, &parsed_input.page_updates);
+use log::debug;
 
-    let part1 = solve_part1(&results);
-    println!("The answer for part 1 is: {}", part1);
+use crate::answer::SolveError;
+use crate::options::{Destination, OptionError, Options, Report};
+use crate::parse::{self, Line, ParseError};
+use crate::solution::Solution;
 
-    let part2 = solve_part2(&results, &parsed_input.page_rules);
-    println!("The answer for part 2 is: {}", part2);
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u32 = 5;
+    const TITLE: &'static str = "Print Queue";
+
+    type Input = ParsedInput;
+    type Output1 = u32;
+    type Output2 = Result<u32, SolveError>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_raw_input(&format_input(input))
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        solve_part1(&check_all_updates(&input.page_rules, &input.page_updates))
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        solve_part2(&check_all_updates(&input.page_rules, &input.page_updates), &input.page_rules, input.unconstrained)
+    }
+
+    fn configure(input: &mut Self::Input, options: &Options) -> Result<(), OptionError> {
+        options.allow_only(&["unconstrained", "explain", "dot", "update"])?;
+        if let Some(placement) = options.parse("unconstrained")? {
+            input.unconstrained = placement;
+        }
+        input.explain = options.is_set("explain");
+        input.dot = options.destination("dot");
+        input.dot_update = match options.parse::<usize>("update")? {
+            None => None,
+            Some(_) if input.dot.is_none() => return Err(OptionError(String::from(
+                "Option 'update' picks the update to draw, so it needs 'dot' too"
+            ))),
+            Some(number) if (1..=input.page_updates.len()).contains(&number) => Some(number - 1),
+            Some(number) => return Err(OptionError(format!(
+                "Invalid update {}, expected a number from 1 to {}", number, input.page_updates.len()
+            ))),
+        };
+        Ok(())
+    }
+
+    /// Lists the rules each rejected update breaks, and draws the rule graph.
+    fn describe(input: &Self::Input) -> Report {
+        let mut report = Report::default();
+        if input.explain {
+            report.lines.extend(input.page_updates.iter().filter_map(|update| {
+                let violated = check_update_set(&input.page_rules, update);
+                let rules: Vec<String> = violated.iter().map(|(before, after)| format!("{}|{}", before, after)).collect();
+                (!violated.is_empty()).then(|| format!("{} breaks {}", join(update, ","), rules.join(", ")))
+            }));
+        }
+        if let Some(dot) = &input.dot {
+            let update = input.dot_update.map(|i| input.page_updates[i].as_slice());
+            report.send(dot, rules_to_dot(&input.page_rules, update), "the rule graph");
+        }
+        report
+    }
 }
 
 fn solve_part1(input: &CheckResults) -> u32 {
     sum_up_midpoints(&input.correct)
 }
 
-fn solve_part2(input: &CheckResults, rules: &HashMap<u32, Page>) -> u32 {
-    let corrected = correct_updates(input, rules);
-    sum_up_midpoints(&corrected)
+fn solve_part2(input: &CheckResults, rules: &HashMap<u32, Page>, unconstrained: Placement) -> Result<u32, SolveError> {
+    let corrected = correct_updates(input, rules, unconstrained)?;
+    Ok(sum_up_midpoints(&corrected))
 }
 
-fn format_input(input: File) -> RawInput {
-    let reader = io::BufReader::new(input);
+fn format_input(input: &str) -> RawInput<'_> {
     let mut should_be_rules = true;
     let mut rules = Vec::new();
     let mut updates = Vec::new();
-    for line in reader.lines() {
-        let line = line.unwrap();
-        if line.is_empty() {
+    for line in parse::lines(input) {
+        if line.text.is_empty() {
             should_be_rules = false;
             continue;
         }
         if should_be_rules {
-            rules.push(line.clone());
+            rules.push(line);
         } else {
             updates.push(line);
         }
@@ -44,16 +97,16 @@ fn format_input(input: File) -> RawInput {
     RawInput { rules, updates }
 }
 
-fn parse_raw_input(raw_input: &RawInput) -> ParsedInput {
-    let page_rules = parse_rules(raw_input.rules.clone());
-    let page_updates = parse_updates(raw_input.updates.clone());
-    ParsedInput { page_rules, page_updates }
+fn parse_raw_input(raw_input: &RawInput) -> Result<ParsedInput, ParseError> {
+    let page_rules = parse_rules(&raw_input.rules)?;
+    let page_updates = parse_updates(&raw_input.updates)?;
+    Ok(ParsedInput { page_rules, page_updates, unconstrained: Placement::Stable, explain: false, dot: None, dot_update: None })
 }
 
-fn parse_rules(raw_rules: Vec<String>) -> HashMap<u32, Page> {
+fn parse_rules(raw_rules: &[Line]) -> Result<HashMap<u32, Page>, ParseError> {
     let mut pages = HashMap::new();
     for rule_line in raw_rules {
-        let rule = parse_rule(rule_line);
+        let rule = parse_rule(rule_line)?;
         
         pages.entry(rule.0)
             .or_insert(Page { 
@@ -71,44 +124,59 @@ fn parse_rules(raw_rules: Vec<String>) -> HashMap<u32, Page> {
             })
             .should_come_after.push(rule.0);
     }
-    pages
+    Ok(pages)
 }
 
-fn parse_rule(line: String) -> (u32, u32) {
-    let pages = line.split("|").collect::<Vec<&str>>().into_iter().map(|s| s.parse::<u32>().unwrap()).collect::<Vec<u32>>();
-    (pages[0], pages[1])
+fn parse_rule(line: &Line) -> Result<(u32, u32), ParseError> {
+    let (before, after) = line.split_once("|", "a rule like 47|53")?;
+    Ok((line.parse(before, "a page number")?, line.parse(after, "a page number")?))
 }
 
-fn parse_updates(updates: Vec<String>) -> Vec<Vec<u32>> {
-    updates.into_iter().map(|update| update.split(",").map(|s| s.parse::<u32>().unwrap()).collect::<Vec<u32>>()).collect::<Vec<Vec<u32>>>()
+/// Parses each update's pages. A page can only be printed once per update, so repeats are
+/// rejected rather than leaving the ordering to guess which copy goes where.
+fn parse_updates(updates: &[Line]) -> Result<Vec<Vec<u32>>, ParseError> {
+    updates.iter().map(|line| {
+        let mut pages = Vec::new();
+        for s in line.text.split(",") {
+            let page = line.parse(s, "a page number")?;
+            if pages.contains(&page) {
+                return Err(line.error(s, "a page that isn't already in the update"));
+            }
+            pages.push(page);
+        }
+        Ok(pages)
+    }).collect()
 }
 
-fn check_all_updates(rules: &HashMap<u32, Page>, updates: &Vec<Vec<u32>>) -> CheckResults {
+fn check_all_updates(rules: &HashMap<u32, Page>, updates: &[Vec<u32>]) -> CheckResults {
     let mut results = CheckResults { correct: Vec::new(), incorrect: Vec::new() };
     for update in updates {
-        if check_update_set(rules, update) {
+        let violated = check_update_set(rules, update);
+        if violated.is_empty() {
             results.correct.push(update.clone());
         } else {
+            debug!("update {} breaks rules {:?}", join(update, ","), violated);
             results.incorrect.push(update.clone());
         }
     }
     results
 }
 
-fn check_update_set(rules: &HashMap<u32, Page>, update_set: &Vec<u32>) -> bool {
-    for i in 0..update_set.len() {
-        let rule = rules.get(&update_set[i]).unwrap();
-        if i > 0 && rule.check_before(update_set[i-1]) {
-            return false;
-        }
-        if i < update_set.len() - 1 && rule.check_after(update_set[i+1]) {
-            return false;
+/// Checks every pair of pages in the update, returning each rule that is broken as
+/// `(before, after)`, in the order the pages appear. Pages without any rules can go anywhere.
+fn check_update_set(rules: &HashMap<u32, Page>, update_set: &[u32]) -> Vec<(u32, u32)> {
+    let mut violated = Vec::new();
+    for (i, &page) in update_set.iter().enumerate() {
+        for &later in &update_set[i + 1..] {
+            if rules.get(&later).is_some_and(|rule| rule.must_come_before(page)) {
+                violated.push((later, page));
+            }
         }
     }
-    true
+    violated
 }
 
-fn sum_up_midpoints(updates: &Vec<Vec<u32>>) -> u32 {
+fn sum_up_midpoints(updates: &[Vec<u32>]) -> u32 {
     let mut sum = 0;
     for update in updates {
         sum += update[update.len() / 2];
@@ -116,71 +184,175 @@ fn sum_up_midpoints(updates: &Vec<Vec<u32>>) -> u32 {
     sum
 }
 
-fn correct_updates(input: &CheckResults, rules: &HashMap<u32, Page>) -> Vec<Vec<u32>> {
-    let incorrect = input.incorrect.clone();
-    let mut corrected = Vec::new();
-    for update in incorrect {
-        corrected.push(correct_update(&update, rules, Vec::new()));
+/// Puts each incorrect update in order, failing on the first one the rules can't order,
+/// since leaving it out would give a wrong sum.
+fn correct_updates(input: &CheckResults, rules: &HashMap<u32, Page>, unconstrained: Placement) -> Result<Vec<Vec<u32>>, SolveError> {
+    input.incorrect.iter()
+        .map(|update| correct_update(update, rules, unconstrained)
+            .map_err(|e| SolveError(format!("update {} can't be ordered, {}", join(update, ","), e))))
+        .collect()
+}
+
+/// Orders the pages of an update by the rules between them. Pages that no rule ties to
+/// the rest of the update are placed by `unconstrained`.
+fn correct_update(update: &[u32], rules: &HashMap<u32, Page>, unconstrained: Placement) -> Result<Vec<u32>, OrderError> {
+    let (free, constrained): (Vec<u32>, Vec<u32>) = update.iter()
+        .partition(|&&page| earlier_pages(rules, page, update).chain(later_pages(rules, page, update)).next().is_none());
+    let ordered = order_pages(&constrained, rules)?;
+    Ok(match unconstrained {
+        Placement::First => free.into_iter().chain(ordered).collect(),
+        Placement::Last => ordered.into_iter().chain(free).collect(),
+        Placement::Stable => {
+            let mut ordered = ordered.into_iter();
+            update.iter().filter_map(|page| if free.contains(page) { Some(*page) } else { ordered.next() }).collect()
+        }
+    })
+}
+
+/// Orders pages by the rules between them, repeatedly taking the one page that no
+/// remaining page has to come before. Fails if the rules go round in a cycle, or if they
+/// allow more than one order.
+fn order_pages(update: &[u32], rules: &HashMap<u32, Page>) -> Result<Vec<u32>, OrderError> {
+    let mut waiting_on: HashMap<u32, usize> = update.iter()
+        .map(|&page| (page, earlier_pages(rules, page, update).count()))
+        .collect();
+    let mut corrected = Vec::with_capacity(update.len());
+    while !waiting_on.is_empty() {
+        let mut ready: Vec<u32> = waiting_on.iter().filter(|(_, &count)| count == 0).map(|(&page, _)| page).collect();
+        ready.sort_unstable();
+        let page = match ready[..] {
+            [page] => page,
+            [] => return Err(OrderError::Cycle(find_cycle(rules, &waiting_on))),
+            _ => return Err(OrderError::Ambiguous(ready)),
+        };
+        waiting_on.remove(&page);
+        for later in later_pages(rules, page, update) {
+            if let Some(count) = waiting_on.get_mut(&later) {
+                *count -= 1;
+            }
+        }
+        corrected.push(page);
     }
-    corrected
+    Ok(corrected)
 }
 
-fn correct_update(update: &Vec<u32>, rules: &HashMap<u32, Page>, pre_corrected: Vec<u32>) -> Vec<u32> {
-    let relevant_rules = find_relevant_rules(update, rules);
-    let mut corrected = Vec::new();
-    let mut to_remove = Vec::new();
-    let mut update = update.clone();
-
-    if update.len() == 1 {
-        corrected.push(relevant_rules[0].number);
-        return add_outsides(&update, &pre_corrected);
-    } else if update.len() == 0 {
-        return pre_corrected;
-    }
-    for i in 0..update.len() {
-        let rule = relevant_rules.get(i).unwrap().clone();
-        if rule.should_come_after.is_empty() {
-            corrected.insert(0, rule.number);
-            to_remove.push(i);
-        } else if rule.should_come_before.is_empty() {
-            corrected.push(rule.number);
-            to_remove.push(i);
+/// Follows rules backwards from the lowest remaining page until a page repeats. Every
+/// remaining page still waits on another remaining page, so this always finds a cycle.
+fn find_cycle(rules: &HashMap<u32, Page>, waiting_on: &HashMap<u32, usize>) -> Vec<u32> {
+    let remaining: Vec<u32> = waiting_on.keys().copied().collect();
+    let mut path = vec![*remaining.iter().min().unwrap()];
+    loop {
+        let page = *path.last().unwrap();
+        let earlier = earlier_pages(rules, page, &remaining).min().unwrap();
+        if let Some(start) = path.iter().position(|&seen| seen == earlier) {
+            let mut cycle = path.split_off(start);
+            cycle.reverse();
+            let lowest = cycle.iter().enumerate().min_by_key(|(_, &page)| page).unwrap().0;
+            cycle.rotate_left(lowest);
+            return cycle;
         }
+        path.push(earlier);
+    }
+}
+
+/// The pages in `pages` that rules say must come before `page`.
+fn earlier_pages<'a>(rules: &'a HashMap<u32, Page>, page: u32, pages: &'a [u32]) -> impl Iterator<Item = u32> + 'a {
+    rules.get(&page).into_iter().flat_map(|rule| &rule.should_come_after).copied().filter(|other| pages.contains(other))
+}
+
+/// The pages in `pages` that rules say must come after `page`.
+fn later_pages<'a>(rules: &'a HashMap<u32, Page>, page: u32, pages: &'a [u32]) -> impl Iterator<Item = u32> + 'a {
+    rules.get(&page).into_iter().flat_map(|rule| &rule.should_come_before).copied().filter(|other| pages.contains(other))
+}
+
+/// Draws the rules as a Graphviz graph with an edge from each page to the pages that
+/// must come after it. Given an update, only its pages are drawn, in update order, and
+/// the rules it breaks are shown in red.
+fn rules_to_dot(rules: &HashMap<u32, Page>, update: Option<&[u32]>) -> Vec<String> {
+    let mut pages: Vec<u32> = match update {
+        Some(update) => update.to_vec(),
+        None => rules.keys().copied().collect(),
+    };
+    if update.is_none() {
+        pages.sort_unstable();
     }
-    for i in to_remove.iter().rev() {
-        update.remove(*i);
+    let violated = update.map(|update| check_update_set(rules, update)).unwrap_or_default();
+    let mut lines = vec![String::from("digraph rules {")];
+    if let Some(update) = update {
+        lines.push(format!("    label=\"update {}\";", join(update, ",")));
     }
-    correct_update(&update, rules, add_outsides(&corrected, &pre_corrected))
+    lines.extend(pages.iter().map(|page| format!("    {};", page)));
+    for &page in &pages {
+        let mut later: Vec<u32> = later_pages(rules, page, &pages).collect();
+        later.sort_unstable();
+        for after in later {
+            let style = if violated.contains(&(page, after)) { " [color=red, penwidth=2]" } else { "" };
+            lines.push(format!("    {} -> {}{};", page, after, style));
+        }
+    }
+    lines.push(String::from("}"));
+    lines
+}
+
+fn join(pages: &[u32], separator: &str) -> String {
+    pages.iter().map(|page| page.to_string()).collect::<Vec<String>>().join(separator)
+}
+
+/// Where `correct_update` puts pages that no rule ties to the rest of their update.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Placement {
+    /// They keep their positions and the other pages are ordered around them.
+    Stable,
+    /// They go before the other pages, in the order they were in.
+    First,
+    /// They go after the other pages, in the order they were in.
+    Last,
 }
 
-fn add_outsides(inside: &Vec<u32>, outside: &Vec<u32>) -> Vec<u32> {
-    let mut result = Vec::new();
-    if outside.len() == 0 {
-        return inside.clone();
+impl FromStr for Placement {
+    type Err = OptionError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "stable" => Ok(Placement::Stable),
+            "first" => Ok(Placement::First),
+            "last" => Ok(Placement::Last),
+            _ => Err(OptionError(format!("Unknown placement '{}', expected stable, first or last", name))),
+        }
     }
-    result.push(outside[0]);
-    result.extend(inside);
-    result.push(outside[1]);
-    result
 }
 
-fn find_relevant_rules(update: &Vec<u32>, rules: &HashMap<u32, Page>) -> Vec<Page> {
-    let mut relevant_rules = Vec::new();
-    for i in 0..update.len() {
-        let rule = rules.get(&update[i]).unwrap();
-        relevant_rules.push(rule.pare(&update));
+/// Why the pages of an update can't be put in a single order.
+#[derive(Debug, PartialEq, Eq)]
+enum OrderError {
+    /// Pages that each have to come before the next, and the last before the first.
+    Cycle(Vec<u32>),
+    /// Pages that could equally go next, because no rule orders them.
+    Ambiguous(Vec<u32>),
+}
+
+impl fmt::Display for OrderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OrderError::Cycle(pages) => write!(f, "the rules form a cycle {} -> {}", join(pages, " -> "), pages[0]),
+            OrderError::Ambiguous(pages) => write!(f, "no rule decides which of pages {} comes first", join(pages, ", ")),
+        }
     }
-    relevant_rules
 }
 
-struct RawInput {
-    rules: Vec<String>,
-    updates: Vec<String>,
+struct RawInput<'a> {
+    rules: Vec<Line<'a>>,
+    updates: Vec<Line<'a>>,
 }
 
-struct ParsedInput {
+pub struct ParsedInput {
     page_rules: HashMap<u32, Page>,
     page_updates: Vec<Vec<u32>>,
+    unconstrained: Placement,
+    explain: bool,
+    /// Where to draw the rule graph, and which update to restrict it to by index.
+    dot: Option<Destination>,
+    dot_update: Option<usize>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -196,20 +368,8 @@ struct CheckResults {
 }
 
 impl Page {
-    fn check_before(&self, update: u32) -> bool {
-        !self.should_come_after.contains(&update)
-    }
-
-    fn check_after(&self, update: u32) -> bool {
-        !self.should_come_before.contains(&update)
-    }
-
-    fn pare(&self, update: &Vec<u32>) -> Page {
-        Page { 
-            number: self.number,
-            should_come_before: self.should_come_before.iter().filter(|&&i| update.contains(&i)).copied().collect(), 
-            should_come_after: self.should_come_after.iter().filter(|&&i| update.contains(&i)).copied().collect() 
-        }
+    fn must_come_before(&self, page: u32) -> bool {
+        self.should_come_before.contains(&page)
     }
 }
 
@@ -218,101 +378,68 @@ impl Page {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::{io::Write, vec};
-    use tempfile::NamedTempFile;
+    use std::vec;
+
+    const EXAMPLE: &str = "\
+47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47
+";
 
     #[test]
     fn it_will_solve_part1() {
-        let mut temp_file = NamedTempFile::new().unwrap();
-        writeln!(temp_file, "47|53").unwrap();
-        writeln!(temp_file, "97|13").unwrap();
-        writeln!(temp_file, "97|61").unwrap();
-        writeln!(temp_file, "97|47").unwrap();
-        writeln!(temp_file, "75|29").unwrap();
-        writeln!(temp_file, "61|13").unwrap();
-        writeln!(temp_file, "75|53").unwrap();
-        writeln!(temp_file, "29|13").unwrap();
-        writeln!(temp_file, "97|29").unwrap();
-        writeln!(temp_file, "53|29").unwrap();
-        writeln!(temp_file, "61|53").unwrap();
-        writeln!(temp_file, "97|53").unwrap();
-        writeln!(temp_file, "61|29").unwrap();
-        writeln!(temp_file, "47|13").unwrap();
-        writeln!(temp_file, "75|47").unwrap();
-        writeln!(temp_file, "97|75").unwrap();
-        writeln!(temp_file, "47|61").unwrap();
-        writeln!(temp_file, "75|61").unwrap();
-        writeln!(temp_file, "47|29").unwrap();
-        writeln!(temp_file, "75|13").unwrap();
-        writeln!(temp_file, "53|13").unwrap();
-        writeln!(temp_file, "").unwrap();
-        writeln!(temp_file, "75,47,61,53,29").unwrap();
-        writeln!(temp_file, "97,61,53,29,13").unwrap();
-        writeln!(temp_file, "75,29,13").unwrap();
-        writeln!(temp_file, "75,97,47,61,53").unwrap();
-        writeln!(temp_file, "61,13,29").unwrap();
-        writeln!(temp_file, "97,13,75,29,47").unwrap();
-        let formatted_input = format_input(temp_file.reopen().unwrap());
-        let parsed_input = parse_raw_input(&formatted_input);
+        let formatted_input = format_input(EXAMPLE);
+        let parsed_input = parse_raw_input(&formatted_input).unwrap();
         let results = check_all_updates(&parsed_input.page_rules, &parsed_input.page_updates);
         assert_eq!(solve_part1(&results), 143);
     }
 
     #[test]
     fn it_will_solve_part2() {
-        let mut temp_file = NamedTempFile::new().unwrap();
-        writeln!(temp_file, "47|53").unwrap();
-        writeln!(temp_file, "97|13").unwrap();
-        writeln!(temp_file, "97|61").unwrap();
-        writeln!(temp_file, "97|47").unwrap();
-        writeln!(temp_file, "75|29").unwrap();
-        writeln!(temp_file, "61|13").unwrap();
-        writeln!(temp_file, "75|53").unwrap();
-        writeln!(temp_file, "29|13").unwrap();
-        writeln!(temp_file, "97|29").unwrap();
-        writeln!(temp_file, "53|29").unwrap();
-        writeln!(temp_file, "61|53").unwrap();
-        writeln!(temp_file, "97|53").unwrap();
-        writeln!(temp_file, "61|29").unwrap();
-        writeln!(temp_file, "47|13").unwrap();
-        writeln!(temp_file, "75|47").unwrap();
-        writeln!(temp_file, "97|75").unwrap();
-        writeln!(temp_file, "47|61").unwrap();
-        writeln!(temp_file, "75|61").unwrap();
-        writeln!(temp_file, "47|29").unwrap();
-        writeln!(temp_file, "75|13").unwrap();
-        writeln!(temp_file, "53|13").unwrap();
-        writeln!(temp_file, "").unwrap();
-        writeln!(temp_file, "75,47,61,53,29").unwrap();
-        writeln!(temp_file, "97,61,53,29,13").unwrap();
-        writeln!(temp_file, "75,29,13").unwrap();
-        writeln!(temp_file, "75,97,47,61,53").unwrap();
-        writeln!(temp_file, "61,13,29").unwrap();
-        writeln!(temp_file, "97,13,75,29,47").unwrap();
-        let formatted_input = format_input(temp_file.reopen().unwrap());
-        let parsed_input = parse_raw_input(&formatted_input);
+        let formatted_input = format_input(EXAMPLE);
+        let parsed_input = parse_raw_input(&formatted_input).unwrap();
         let results = check_all_updates(&parsed_input.page_rules, &parsed_input.page_updates);
-        assert_eq!(solve_part2(&results, &parsed_input.page_rules), 123);
+        assert_eq!(solve_part2(&results, &parsed_input.page_rules, Placement::Stable), Ok(123));
     }
+
     #[test]
     fn it_will_format_the_input_file() {
-        let mut temp_file = NamedTempFile::new().unwrap();
-        writeln!(temp_file, "1|2").unwrap();
-        writeln!(temp_file, "2|3").unwrap();
-        writeln!(temp_file, "3|4").unwrap();
-        writeln!(temp_file, "").unwrap();
-        writeln!(temp_file, "1").unwrap();
-        writeln!(temp_file, "2").unwrap();
-        writeln!(temp_file, "3").unwrap();
-        let input = format_input(temp_file.reopen().unwrap());
-        assert_eq!(input.rules, vec!["1|2", "2|3", "3|4"]);
-        assert_eq!(input.updates, vec!["1", "2", "3"]);
+        let input = "1|2\n2|3\n3|4\n\n1\n2\n3\n";
+        let input = format_input(input);
+        assert_eq!(input.rules.iter().map(|line| line.text).collect::<Vec<&str>>(), vec!["1|2", "2|3", "3|4"]);
+        assert_eq!(input.updates.iter().map(|line| line.text).collect::<Vec<&str>>(), vec!["1", "2", "3"]);
+        assert_eq!(input.updates[0].number, 5);
     }
 
     #[test]
     fn it_will_parse_rules() {
-        let rules = vec![String::from("1|2"), String::from("2|3"), String::from("3|4")];
-        let pages = parse_rules(rules);
+        let rules = parse::lines("1|2\n2|3\n3|4\n").collect::<Vec<Line>>();
+        let pages = parse_rules(&rules).unwrap();
         assert_eq!(pages.len(), 4);
         assert_eq!(pages.get(&1).unwrap().should_come_before, vec![2]);
         assert_eq!(pages.get(&2).unwrap().should_come_before, vec![3]);
@@ -326,28 +453,53 @@ mod tests {
 
     #[test]
     fn it_will_parse_a_rule() {
-        let rule = parse_rule(String::from("1|2"));
-        assert_eq!(rule, (1, 2));
+        let rule = parse_rule(&parse::lines("1|2").next().unwrap());
+        assert_eq!(rule, Ok((1, 2)));
     }
 
     #[test]
     fn it_will_parse_updates() {
-        let updates = vec![String::from("1,2,3"), String::from("4,5,6")];
-        let parsed_updates = parse_updates(updates);
-        assert_eq!(parsed_updates, vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        let updates = parse::lines("1,2,3\n4,5,6\n").collect::<Vec<Line>>();
+        let parsed_updates = parse_updates(&updates);
+        assert_eq!(parsed_updates, Ok(vec![vec![1, 2, 3], vec![4, 5, 6]]));
     }
 
     #[test]
     fn it_will_parse_raw_input() {
-        let raw_input = RawInput { 
-            rules: vec![String::from("1|2"), String::from("2|3"), String::from("3|4")], 
-            updates: vec![String::from("1,2,3"), String::from("4,5,6")]
-        };
-        let parsed_input = parse_raw_input(&raw_input);
+        let parsed_input = parse_raw_input(&format_input("1|2\n2|3\n3|4\n\n1,2,3\n4,5,6\n")).unwrap();
         assert_eq!(parsed_input.page_rules.len(), 4);
         assert_eq!(parsed_input.page_updates, vec![vec![1, 2, 3], vec![4, 5, 6]]);
     }
 
+    #[test]
+    fn it_will_report_malformed_rules_and_updates() {
+        let error = parse_raw_input(&format_input("1|2\n2-3\n\n1,2\n")).err().unwrap();
+        assert_eq!((error.line, error.column), (2, 4));
+
+        let error = parse_raw_input(&format_input("1|2\n2|3\n\n1,2\n3,x\n")).err().unwrap();
+        assert_eq!((error.line, error.column), (5, 3));
+
+        let error = parse_raw_input(&format_input("2|1\n\n1,2,1\n")).err().unwrap();
+        assert_eq!((error.line, error.column), (3, 5));
+    }
+
+    #[test]
+    fn it_will_place_pages_without_rules() {
+        let parsed_input = parse_raw_input(&format_input("1|2\n2|3\n\n7,3,1,2\n3,8,2\n")).unwrap();
+        let rules = &parsed_input.page_rules;
+        let results = check_all_updates(rules, &parsed_input.page_updates);
+        assert_eq!(results.incorrect, vec![vec![7, 3, 1, 2], vec![3, 8, 2]]);
+        assert!(check_update_set(rules, &[7, 1, 9, 2]).is_empty());
+        assert_eq!(check_update_set(rules, &[2, 9, 1]), vec![(1, 2)]);
+
+        assert_eq!(correct_update(&[7, 3, 1, 2], rules, Placement::Stable), Ok(vec![7, 1, 2, 3]));
+        assert_eq!(correct_update(&[3, 8, 2], rules, Placement::Stable), Ok(vec![2, 8, 3]));
+        assert_eq!(correct_update(&[3, 8, 2], rules, Placement::First), Ok(vec![8, 2, 3]));
+        assert_eq!(correct_update(&[3, 8, 2], rules, Placement::Last), Ok(vec![2, 3, 8]));
+        assert_eq!(solve_part2(&results, rules, Placement::Last), Ok(3 + 3));
+        assert!("middle".parse::<Placement>().is_err());
+    }
+
     #[test]
     fn it_will_determine_if_a_set_of_updates_is_in_the_correct_order() {
         let rules = HashMap::from([
@@ -357,21 +509,89 @@ mod tests {
             (4, Page { number: 4, should_come_before: vec![], should_come_after: vec![3] }),
         ]);
         let update_set = vec![1, 2, 3];
-        assert_eq!(check_update_set(&rules, &update_set), true);
+        assert!(check_update_set(&rules, &update_set).is_empty());
 
         let update_set = vec![1, 3, 2];
-        assert_eq!(check_update_set(&rules, &update_set), false);
+        assert_eq!(check_update_set(&rules, &update_set), vec![(2, 3)]);
+    }
+
+    #[test]
+    fn it_will_find_violations_between_pages_that_are_not_neighbours() {
+        let parsed_input = parse_raw_input(&format_input(EXAMPLE)).unwrap();
+        let rules = &parsed_input.page_rules;
+        assert_eq!(check_update_set(rules, &[13, 75, 29, 97]), vec![(75, 13), (29, 13), (97, 13), (97, 75), (97, 29)]);
+
+        let rules = parse_rules(&parse::lines("1|3\n").collect::<Vec<Line>>()).unwrap();
+        assert_eq!(check_update_set(&rules, &[3, 2, 1]), vec![(1, 3)]);
+    }
+
+    #[test]
+    fn it_will_explain_rejected_updates() {
+        let mut parsed_input = parse_raw_input(&format_input(EXAMPLE)).unwrap();
+        let mut options = Options::default();
+        options.insert("explain");
+        Day05::configure(&mut parsed_input, &options).unwrap();
+        assert_eq!(Day05::describe(&parsed_input).lines, vec![
+            "75,97,47,61,53 breaks 97|75",
+            "61,13,29 breaks 29|13",
+            "97,13,75,29,47 breaks 75|13, 29|13, 47|13, 47|29",
+        ]);
+
+        let answers = crate::find_day(5).unwrap().run_with(EXAMPLE, crate::solution::Part::Two, &options).unwrap();
+        assert_eq!(answers.report.len(), 3);
+    }
+
+    #[test]
+    fn it_will_draw_the_rule_graph() {
+        let rules = parse_rules(&parse::lines("1|2\n2|3\n1|3\n3|4\n").collect::<Vec<Line>>()).unwrap();
+        assert_eq!(rules_to_dot(&rules, None).join("\n"), "\
+digraph rules {
+    1;
+    2;
+    3;
+    4;
+    1 -> 2;
+    1 -> 3;
+    2 -> 3;
+    3 -> 4;
+}");
+        assert_eq!(rules_to_dot(&rules, Some(&[3, 1, 2])).join("\n"), "\
+digraph rules {
+    label=\"update 3,1,2\";
+    3;
+    1;
+    2;
+    1 -> 2;
+    1 -> 3 [color=red, penwidth=2];
+    2 -> 3 [color=red, penwidth=2];
+}");
+    }
+
+    #[test]
+    fn it_will_only_draw_updates_that_exist() {
+        let mut parsed_input = parse_raw_input(&format_input(EXAMPLE)).unwrap();
+        let mut options = Options::default();
+        options.insert("dot");
+        options.insert("update=4");
+        Day05::configure(&mut parsed_input, &options).unwrap();
+        let report = Day05::describe(&parsed_input).lines;
+        assert_eq!(report[1], "    label=\"update 75,97,47,61,53\";");
+        assert!(report.contains(&String::from("    97 -> 75 [color=red, penwidth=2];")));
+
+        options.insert("update=7");
+        assert!(Day05::configure(&mut parsed_input, &options).is_err());
+
+        let mut options = Options::default();
+        options.insert("update=1");
+        assert!(Day05::configure(&mut parsed_input, &options).is_err());
     }
 
     #[test]
     fn page_will_check_if_the_update_is_correct() {
         let page = Page { number: 1, should_come_before: vec![2], should_come_after: vec![3] };
-        assert_eq!(page.check_before(2), true);
-        assert_eq!(page.check_before(3), false);
-        assert_eq!(page.check_before(4), true); // ???
-        assert_eq!(page.check_after(2), false);
-        assert_eq!(page.check_after(3), true);
-        assert_eq!(page.check_after(4), true);
+        assert!(page.must_come_before(2));
+        assert!(!page.must_come_before(3));
+        assert!(!page.must_come_before(4));
     }
 
     #[test]
@@ -384,6 +604,10 @@ mod tests {
                 (4, Page { number: 4, should_come_before: vec![], should_come_after: vec![3] }),
             ]),
             page_updates: vec![vec![1, 2, 3], vec![1, 3, 2], vec![3, 4], vec![4,3]],
+            unconstrained: Placement::Stable,
+            explain: false,
+            dot: None,
+            dot_update: None,
         };
         let expected = CheckResults { correct: vec![vec![1,2,3], vec![3,4]], incorrect: vec![vec![1,3,2], vec![4,3]] };
         let results = check_all_updates(&input.page_rules, &input.page_updates);
@@ -406,14 +630,35 @@ mod tests {
             (3, Page { number: 3, should_come_before: vec![4], should_come_after: vec![2] }),
             (4, Page { number: 4, should_come_before: vec![], should_come_after: vec![3] }),
         ]);
-        assert_eq!(correct_update(&update, &rules, Vec::new()), vec![1,2,3]);
+        assert_eq!(correct_update(&update, &rules, Placement::Stable), Ok(vec![1,2,3]));
+
+        let parsed_input = parse_raw_input(&format_input(EXAMPLE)).unwrap();
+        assert_eq!(correct_update(&[97, 13, 75, 29, 47], &parsed_input.page_rules, Placement::Stable), Ok(vec![97, 75, 47, 29, 13]));
+    }
+
+    #[test]
+    fn it_will_report_a_cycle_instead_of_ordering_an_update() {
+        let rules = parse_rules(&parse::lines("1|2\n2|3\n3|1\n3|4\n").collect::<Vec<Line>>()).unwrap();
+        let error = correct_update(&[4, 3, 2, 1], &rules, Placement::Stable).unwrap_err();
+        assert_eq!(error, OrderError::Cycle(vec![1, 2, 3]));
+        assert_eq!(error.to_string(), "the rules form a cycle 1 -> 2 -> 3 -> 1");
+        assert_eq!(correct_update(&[3, 1], &rules, Placement::Stable), Ok(vec![3, 1]));
+    }
+
+    #[test]
+    fn it_will_report_pages_the_rules_do_not_order() {
+        let rules = parse_rules(&parse::lines("1|2\n1|3\n").collect::<Vec<Line>>()).unwrap();
+        let error = correct_update(&[3, 2, 1], &rules, Placement::Stable).unwrap_err();
+        assert_eq!(error, OrderError::Ambiguous(vec![2, 3]));
+        assert_eq!(error.to_string(), "no rule decides which of pages 2, 3 comes first");
     }
 
     #[test]
-    fn it_will_add_outsides() {
-        let inside = vec![1,2,3];
-        let outside = vec![4,5];
-        assert_eq!(add_outsides(&inside, &outside), vec![4,1,2,3,5]);
+    fn it_will_fail_part2_when_an_update_cannot_be_ordered() {
+        let day = crate::find_day(5).unwrap();
+        let error = day.run("1|2\n1|3\n\n3,2,1\n", crate::solution::Part::Two).unwrap_err();
+        assert_eq!(error.to_string(), "part 2 could not be solved: update 3,2,1 can't be ordered, no rule decides which of pages 2, 3 comes first");
+        assert!(day.run("1|2\n1|3\n\n3,2,1\n", crate::solution::Part::One).is_ok());
     }
 }
 
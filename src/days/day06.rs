@@ -1,86 +1,117 @@
-use std::io::{self, BufRead};
-use std::fs::File;
 use std::collections::HashSet;
-pub fn solve(input: File) {
-    let formatted = format_input(input);
+use std::fmt;
 
-    let part1 = solve_part1(&formatted.clone());
-    println!("The answer for part 1 is: {}", part1);
+use log::{debug, trace};
 
-    let part2 = solve_part2(&formatted.clone());
-    println!("The answer for part 2 is: {}", part2);
+use crate::grid::Grid;
+use crate::parallel;
+use crate::parse::{self, ParseError};
+use crate::solution::Solution;
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    const DAY: u32 = 6;
+    const TITLE: &'static str = "Guard Gallivant";
+
+    type Input = Grid<Tile>;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        format_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        solve_part2(input)
+    }
 }
 
-pub fn solve_part1(input: &Vec<Vec<String>>) -> i32 {
-    let mut guard = find_guard(&input);
-    guard.predict_path(&input);
+pub fn solve_part1(input: &Grid<Tile>) -> i32 {
+    let guard = find_guard(input).predict_path(input);
     get_distinct_positions(&guard.path).len() as i32
 }
 
-pub fn solve_part2(input: &Vec<Vec<String>>) -> i32 {
-    let looped = find_loops(&input);
+pub fn solve_part2(input: &Grid<Tile>) -> i32 {
+    let looped = find_loops(input);
 
     looped.len() as i32
 }
 
-fn format_input(input: File) -> Vec<Vec<String>> {
-    let reader = io::BufReader::new(input);
-    let content: Vec<Vec<String>> = reader.lines()
-        .map(|line| {
-            line
-                .unwrap()
-                .chars()
-                .map(|c| c.to_string())
-                .collect::<Vec<String>>()
-        })
-        .collect::<Vec<Vec<String>>>();
-    content
+fn format_input(input: &str) -> Result<Grid<Tile>, ParseError> {
+    let map = Grid::parse(input, Tile::from_char, "'.', '#' or '^'")?;
+    if map.find(|&tile| tile == Tile::Guard).is_none() {
+        let last = parse::lines(input).last().unwrap_or(parse::Line { number: 1, text: "" });
+        return Err(last.error_at_end("a guard '^' somewhere on the map"));
+    }
+    Ok(map)
 }
 
-fn find_guard(input: &Vec<Vec<String>>) -> Guard {
-    for (i, row) in input.iter().enumerate() {
-        for (j, cell) in row.iter().enumerate() {
-            if cell == "^" {
-                return Guard { 
-                    direction: Direction::Up, 
-                    position: (i, j), 
-                    path: vec![Vector { x: i, y: j, dir: Direction::Up }], 
-                    bounds: (input.len(), row.len()), 
-                };
-            }
-        }
+fn find_guard(input: &Grid<Tile>) -> Guard {
+    let position = input.find(|&tile| tile == Tile::Guard).expect("the map should have a guard");
+    Guard::new(position, (input.height(), input.width()))
+}
+
+/// Each position on the path once, in the order the guard first reaches it.
+fn get_distinct_positions(path: &[Vector]) -> Vec<(usize, usize)> {
+    let mut seen = HashSet::new();
+    path.iter().map(|v| (v.x, v.y)).filter(|&position| seen.insert(position)).collect()
+}
+
+/// An obstacle can only change the guard's route if it's somewhere on that route, so
+/// those are the only cells worth trying. Each trial is independent, so they run in parallel.
+fn find_loops(input: &Grid<Tile>) -> Vec<(usize, usize)> {
+    let guard = find_guard(input);
+    let route = guard.clone().predict_path(input);
+    let candidates = get_distinct_positions(&route.path)
+        .into_iter()
+        .filter(|&position| input[position] == Tile::Open)
+        .collect::<Vec<(usize, usize)>>();
+    parallel::filter(&candidates, |&position| loops_with_obstacle(input, &guard, position))
+}
+
+fn loops_with_obstacle(input: &Grid<Tile>, guard: &Guard, obstacle: (usize, usize)) -> bool {
+    trace!("trying an obstacle at {:?}", obstacle);
+    let looped = guard.clone().with_obstacle(obstacle).predict_path(input).is_loop();
+    if looped {
+        debug!("loop found with an obstacle at {:?}", obstacle);
     }
-    Guard { direction: Direction::Up, position: (0, 0), path: vec![Vector { x: 0, y: 0, dir: Direction::Up }], bounds: (0, 0) }
+    looped
 }
 
-fn get_distinct_positions(path: &Vec<Vector>) -> Vec<(usize, usize)> {
-    path.iter().map(|v| (v.x, v.y)).collect::<HashSet<(usize, usize)>>().into_iter().collect()
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Tile {
+    Open,
+    Obstacle,
+    Guard,
 }
 
-fn find_loops(input: &Vec<Vec<String>>) -> Vec<(usize, usize)> {
-    let guard = find_guard(&input);
-    let mut looped = Vec::new();
-    let mut input = input.clone();
-    for i in 0..input.len() {
-        println!("i: {}", i);
-        for j in 0..input[i].len() {
-            println!("    j: {}", j);
-            if input[i][j] != "."  {
-                continue;
-            }
-            input[i][j] = String::from("#");
-            let trial = guard.clone().predict_path(&input);
-            if trial.is_loop() {
-                println!("     - loop found\n");
-                looped.push((i, j));
-            }
-            input[i][j] = String::from(".");
+impl Tile {
+    fn from_char(c: char) -> Option<Tile> {
+        match c {
+            '.' => Some(Tile::Open),
+            '#' => Some(Tile::Obstacle),
+            '^' => Some(Tile::Guard),
+            _ => None,
         }
     }
-    looped
 }
 
-#[derive(Clone, PartialEq, Eq, Debug)]
+impl fmt::Display for Tile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Tile::Open => write!(f, "."),
+            Tile::Obstacle => write!(f, "#"),
+            Tile::Guard => write!(f, "^"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 enum Direction {
     Up,
     Down,
@@ -91,10 +122,10 @@ enum Direction {
 impl Direction {
     fn move_forward(&self, position: (usize, usize)) -> Vector {
         match self {
-            Direction::Up => Vector { x: position.0 - 1, y: position.1, dir: self.clone() },
-            Direction::Down => Vector { x: position.0 + 1, y: position.1, dir: self.clone() },
-            Direction::Left => Vector { x: position.0, y: position.1 - 1, dir: self.clone() },
-            Direction::Right => Vector { x: position.0, y: position.1 + 1, dir: self.clone() },
+            Direction::Up => Vector { x: position.0 - 1, y: position.1, dir: *self },
+            Direction::Down => Vector { x: position.0 + 1, y: position.1, dir: *self },
+            Direction::Left => Vector { x: position.0, y: position.1 - 1, dir: *self },
+            Direction::Right => Vector { x: position.0, y: position.1 + 1, dir: *self },
         }
     }
 
@@ -106,6 +137,16 @@ impl Direction {
             Direction::Right => Direction::Down,
         }
     }
+
+    /// A distinct bit per direction, for recording which ways a cell has been crossed.
+    fn bit(&self) -> u8 {
+        match self {
+            Direction::Up => 1,
+            Direction::Down => 2,
+            Direction::Left => 4,
+            Direction::Right => 8,
+        }
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -121,17 +162,41 @@ struct Guard {
     position: (usize, usize),
     path: Vec<Vector>,
     bounds: (usize, usize),
+    /// The directions the guard has already walked through each cell in, as `Direction` bits.
+    /// Reaching a cell facing a way it has been faced before means the guard is in a loop.
+    visited: Grid<u8>,
+    looped: bool,
+    /// An extra obstacle placed on the map, so trials don't need their own copy of it.
+    obstacle: Option<(usize, usize)>,
 }
 
 impl Guard {
-    fn predict_path(&mut self, input: &Vec<Vec<String>>) -> Guard {
+    fn new(position: (usize, usize), bounds: (usize, usize)) -> Guard {
+        let mut visited = Grid::new(bounds.1, bounds.0, 0);
+        visited[position] = Direction::Up.bit();
+        Guard {
+            direction: Direction::Up,
+            position,
+            path: vec![Vector { x: position.0, y: position.1, dir: Direction::Up }],
+            bounds,
+            visited,
+            looped: false,
+            obstacle: None,
+        }
+    }
+
+    fn with_obstacle(self, obstacle: (usize, usize)) -> Guard {
+        Guard { obstacle: Some(obstacle), ..self }
+    }
+
+    fn predict_path(mut self, input: &Grid<Tile>) -> Guard {
         while !self.leaving_bounds() && !self.is_loop() {
-            self.traverse(&input);
+            self.traverse(input);
         }
-        self.clone()
+        self
     }
 
-    fn traverse(&mut self, input: &Vec<Vec<String>>) {
+    fn traverse(&mut self, input: &Grid<Tile>) {
         if self.check_move(input) {
             self.move_forward();
         } else {
@@ -139,19 +204,28 @@ impl Guard {
         }
     }
 
-    fn check_move(&mut self, input: &Vec<Vec<String>>) -> bool {
+    fn check_move(&mut self, input: &Grid<Tile>) -> bool {
         let new_pos = self.direction.move_forward(self.position);
-        input[new_pos.x][new_pos.y] != "#"
+        input[(new_pos.x, new_pos.y)] != Tile::Obstacle && self.obstacle != Some((new_pos.x, new_pos.y))
     }
 
     fn move_forward(&mut self) {
         let new_pos = self.direction.move_forward(self.position);
         self.position = (new_pos.x, new_pos.y);
-        self.path.push(Vector { x: self.position.0, y: self.position.1, dir: self.direction.clone() });
+        self.path.push(Vector { x: self.position.0, y: self.position.1, dir: self.direction });
+        self.visit();
     }
 
+    /// Turning is a new state too, so a guard boxed in on every side loops by turning.
     fn turn_right(&mut self) {
         self.direction = self.direction.turn_right();
+        self.visit();
+    }
+
+    fn visit(&mut self) {
+        let seen = &mut self.visited[self.position];
+        self.looped = *seen & self.direction.bit() != 0;
+        *seen |= self.direction.bit();
     }
 
     fn leaving_bounds(&self) -> bool {
@@ -164,97 +238,113 @@ impl Guard {
     }
 
     fn is_loop(&self) -> bool {
-        let mut path = self.path.clone();
-        let last_pos = path.pop().unwrap();
-        if path.contains(&last_pos) {
-            println!("path: {:?}, last_pos: {:?}", path, last_pos);
-            return true;
-        }
-        false
+        self.looped
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::{io::Write, vec};
-    use tempfile::NamedTempFile;
+
+    const EXAMPLE: &str = "\
+....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...
+";
 
     #[test]
     fn it_will_solve_part_one() {
-        let mut temp_file = NamedTempFile::new().unwrap();
-        writeln!(temp_file, "....#.....").unwrap();
-        writeln!(temp_file, ".........#").unwrap();
-        writeln!(temp_file, "..........").unwrap();
-        writeln!(temp_file, "..#.......").unwrap();
-        writeln!(temp_file, ".......#..").unwrap();
-        writeln!(temp_file, "..........").unwrap();
-        writeln!(temp_file, ".#..^.....").unwrap();
-        writeln!(temp_file, "........#.").unwrap();
-        writeln!(temp_file, "#.........").unwrap();
-        writeln!(temp_file, "......#...").unwrap();
-        let formatted = format_input(temp_file.reopen().unwrap());
+        let formatted = format_input(EXAMPLE).unwrap();
         let part1 = solve_part1(&formatted);
         assert_eq!(part1, 41);
     }
 
     #[test]
     fn it_will_solve_part_two() {
-        let mut temp_file = NamedTempFile::new().unwrap();
-        writeln!(temp_file, "....#.....").unwrap();
-        writeln!(temp_file, ".........#").unwrap();
-        writeln!(temp_file, "..........").unwrap();
-        writeln!(temp_file, "..#.......").unwrap();
-        writeln!(temp_file, ".......#..").unwrap();
-        writeln!(temp_file, "..........").unwrap();
-        writeln!(temp_file, ".#..^.....").unwrap();
-        writeln!(temp_file, "........#.").unwrap();
-        writeln!(temp_file, "#.........").unwrap();
-        writeln!(temp_file, "......#...").unwrap();
-        let formatted = format_input(temp_file.reopen().unwrap());
+        let formatted = format_input(EXAMPLE).unwrap();
         let part1 = solve_part2(&formatted);
         assert_eq!(part1, 6);
     }
 
+    #[test]
+    fn it_will_detect_a_loop() {
+        let input = format_input(".#..\n...#\n#^..\n..#.\n").unwrap();
+        let guard = find_guard(&input).predict_path(&input);
+        assert!(guard.is_loop());
+        assert_eq!(guard.position, (2, 1));
+        assert_eq!(guard.direction, Direction::Up);
+
+        let input = format_input(".#..\n....\n#^..\n..#.\n").unwrap();
+        assert!(!find_guard(&input).predict_path(&input).is_loop());
+    }
+
+    #[test]
+    fn it_will_detect_a_guard_boxed_in() {
+        let input = format_input(".#.\n.^#\n.#.\n").unwrap();
+        let guard = find_guard(&input).with_obstacle((1, 0)).predict_path(&input);
+        assert!(guard.is_loop());
+        assert_eq!(guard.path.len(), 1);
+        assert_eq!(find_loops(&input), vec![(1, 0)]);
+    }
+
+    #[test]
+    fn it_will_only_try_obstacles_on_the_route() {
+        let input = format_input(EXAMPLE).unwrap();
+        let mut looped = find_loops(&input);
+        let route = find_guard(&input).predict_path(&input);
+        assert!(looped.iter().all(|position| get_distinct_positions(&route.path).contains(position)));
+        looped.sort();
+        assert_eq!(looped, vec![(6, 3), (7, 6), (7, 7), (8, 1), (8, 3), (9, 7)]);
+    }
+
+    #[test]
+    fn it_will_find_the_same_loops_as_a_sequential_search() {
+        let input = format_input(EXAMPLE).unwrap();
+        let guard = find_guard(&input);
+        let sequential = input.positions()
+            .filter(|&position| input[position] == Tile::Open && loops_with_obstacle(&input, &guard, position))
+            .collect::<HashSet<(usize, usize)>>();
+        assert_eq!(find_loops(&input).into_iter().collect::<HashSet<(usize, usize)>>(), sequential);
+    }
+
     #[test]
     fn it_will_format_the_input_correctly() {
-        let mut temp_file = NamedTempFile::new().unwrap();
-        writeln!(temp_file, "....#").unwrap();
-        writeln!(temp_file, ".....").unwrap();
-        writeln!(temp_file, "....^").unwrap();
-        writeln!(temp_file, "..#..").unwrap();
-        writeln!(temp_file, ".....").unwrap();
-        let formatted = format_input(temp_file.reopen().unwrap());
-        assert_eq!(formatted, vec![
-            vec![".", ".", ".", ".", "#"],
-            vec![".", ".", ".", ".", "."],
-            vec![".", ".", ".", ".", "^"],
-            vec![".", ".", "#",".", "."],
-            vec![".", ".", ".", ".", "."]
-        ]);
+        let input = "....#\n.....\n....^\n..#..\n.....\n";
+        let formatted = format_input(input).unwrap();
+        assert_eq!((formatted.width(), formatted.height()), (5, 5));
+        assert_eq!(formatted[(0, 4)], Tile::Obstacle);
+        assert_eq!(formatted[(2, 4)], Tile::Guard);
+        assert_eq!(formatted[(3, 2)], Tile::Obstacle);
+        assert_eq!(formatted.to_string(), input);
+    }
+
+    #[test]
+    fn it_will_require_a_guard() {
+        let error = format_input("..#\n...\n").err().unwrap();
+        assert_eq!((error.line, error.column), (2, 4));
     }
 
     #[test]
     fn it_will_find_the_caret() {
-        let input = vec![
-            vec![String::from("."), String::from("."), String::from("."), String::from("#"), String::from(".")],
-            vec![String::from("."), String::from("."), String::from("."), String::from("."), String::from("#")],
-            vec![String::from("."), String::from("."), String::from("."), String::from("^"), String::from(".")],
-            vec![String::from("."), String::from("#"), String::from("."), String::from("."), String::from(".")],
-            vec![String::from("."), String::from("."), String::from("."), String::from("#"), String::from(".")]
-        ];
+        let input = format_input("...#.\n....#\n...^.\n.#...\n...#.\n").unwrap();
         let result = find_guard(&input);
-        assert_eq!(result, Guard { direction: Direction::Up, position: (2, 3), path: vec![Vector { x: 2, y: 3, dir: Direction::Up }], bounds: (5, 5)});
+        assert_eq!(result.direction, Direction::Up);
+        assert_eq!(result.position, (2, 3));
+        assert_eq!(result.path, vec![Vector { x: 2, y: 3, dir: Direction::Up }]);
+        assert_eq!(result.bounds, (5, 5));
     }
 
     #[test]
     fn it_will_predict_the_path() {
-        let mut guard = Guard { direction: Direction::Up, position: (2, 3), path: vec![Vector { x: 2, y: 3, dir: Direction::Up }], bounds: (3, 2) };
-        let input = vec![
-            vec![String::from("."), String::from("."), String::from("."), String::from("#"), String::from(".")],
-            vec![String::from("."), String::from("."), String::from("."), String::from("."), String::from("#")],
-            vec![String::from("."), String::from("."), String::from("."), String::from("^"), String::from(".")]
-        ];
+        let input = format_input("...#.\n....#\n...^.\n").unwrap();
+        let guard = find_guard(&input);
         let result = guard.predict_path(&input).path;
         assert_eq!(result, vec![
             Vector { x: 2, y: 3, dir: Direction::Up },
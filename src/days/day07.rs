@@ -1,182 +1,561 @@
-use std::fs::File;
-use std::io::{self, BufRead};
+use std::fmt::{self, Debug};
 
-pub fn solve(input: File) {
-    let formatted = format_input(input);
+use log::{debug, trace};
+use num_bigint::BigInt;
+use num_traits::{One, Signed, Zero};
 
-    let part1 = solve_part1(&formatted);
-    println!("The answer for part 1 is: {}", part1);
+use crate::options::{OptionError, Options, Report};
+use crate::parallel;
+use crate::parse::{self, ParseError};
+use crate::solution::Solution;
 
-    let part2 = solve_part2(&formatted);
-    println!("The answer for part 2 is: {}", part2);
+pub struct Day07;
+
+impl Solution for Day07 {
+    const DAY: u32 = 7;
+    const TITLE: &'static str = "Bridge Repair";
+
+    type Input = Calibrations;
+    type Output1 = BigInt;
+    type Output2 = BigInt;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        format_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        solve_part2(input)
+    }
+
+    fn configure(input: &mut Self::Input, options: &Options) -> Result<(), OptionError> {
+        options.allow_only(&["operators", "explain", "count"])?;
+        if let Some(operators) = options.get("operators")? {
+            input.operators = Some(parse_operators(operators)?);
+        }
+        input.explain = options.is_set("explain") || options.is_set("count");
+        input.count = options.is_set("count");
+        Ok(())
+    }
+
+    fn report(input: &Self::Input, part: u32) -> Report {
+        if !input.explain {
+            return Report::default();
+        }
+        let default = if part == 1 { PART1_OPERATORS } else { PART2_OPERATORS };
+        let explanations = explain_calibrations(&input.calibrations, input.operators_or(default), input.count);
+        let mut lines = vec![format!("Part {}: {} calibrations can be solved", part, explanations.len())];
+        lines.extend(explanations.iter().map(|explanation| format!("  {}", explanation)));
+        lines.into()
+    }
 }
 
-fn solve_part1(input: &Vec<Calibration>) -> i64 {
-    let valid_calibrations = check_calibrations(&input);
+/// The operators each part uses unless a run picks its own with `--option operators=...`.
+const PART1_OPERATORS: &[&dyn Operator] = &[&Add, &Multiply];
+const PART2_OPERATORS: &[&dyn Operator] = &[&Add, &Multiply, &Concat];
+
+fn solve_part1(input: &Calibrations) -> BigInt {
+    let valid_calibrations = check_calibrations(&input.calibrations, input.operators_or(PART1_OPERATORS));
     sum_calibrations(&valid_calibrations)
 }
 
-fn solve_part2(input: &Vec<Calibration>) -> i64 {
-    let valid_calibrations = check_calibrations_split(&input);
+fn solve_part2(input: &Calibrations) -> BigInt {
+    let valid_calibrations = check_calibrations(&input.calibrations, input.operators_or(PART2_OPERATORS));
     sum_calibrations(&valid_calibrations)
 }
 
-fn format_input(input: File) -> Vec<Calibration> {
-    let mut parsed_input = Vec::new();
-    let reader = io::BufReader::new(input);
-    for line in reader.lines() {
-        let line_text = line.unwrap();
-        let result_to_terms = line_text.split(":").map(|s| s.trim()).collect::<Vec<&str>>();
-        let result = result_to_terms[0].parse::<i64>().unwrap();
-        let terms = result_to_terms[1].split(" ").map(|s| s.parse::<i64>().unwrap()).collect::<Vec<i64>>();
-        parsed_input.push(Calibration { result, terms });
+fn format_input(input: &str) -> Result<Calibrations, ParseError> {
+    let mut calibrations = Vec::new();
+    for line in parse::lines(input) {
+        let (result, terms) = line.split_once(":", "a result followed by ':'")?;
+        let result = line.parse(result.trim(), "a number")?;
+        let terms = terms.split_whitespace().map(|s| line.parse(s, "a number")).collect::<Result<Vec<BigInt>, _>>()?;
+        if terms.is_empty() {
+            return Err(line.error_at_end("at least one term"));
+        }
+        calibrations.push(Calibration { result, terms });
     }
-    parsed_input
+    Ok(Calibrations { calibrations, operators: None, explain: false, count: false })
 }
 
-fn check_calibrations(calibrations: &Vec<Calibration>) -> Vec<Calibration> {
-    let mut results = vec![];
-    for calibration in calibrations {
-        if check_calibration(&calibration.result, &mut calibration.terms.clone(), Operation::Add, false) 
-            || check_calibration(&calibration.result, &mut calibration.terms.clone(), Operation::Multiply, false) {
-            results.push(calibration.clone());
-        }
+/// Parses a comma separated operator set such as `+,*,||`.
+pub fn parse_operators(list: &str) -> Result<Vec<&'static dyn Operator>, OptionError> {
+    list.split(',')
+        .map(|symbol| operator(symbol.trim()).ok_or_else(|| OptionError(format!(
+            "Unknown operator '{}', expected a list of +, *, ||, - or /", symbol
+        ))))
+        .collect()
+}
+
+fn operator(symbol: &str) -> Option<&'static dyn Operator> {
+    match symbol {
+        "+" => Some(&Add),
+        "*" => Some(&Multiply),
+        "||" => Some(&Concat),
+        "-" => Some(&Subtract),
+        "/" => Some(&Divide),
+        _ => None,
+    }
+}
+
+fn check_calibrations(calibrations: &[Calibration], operators: &[&'static dyn Operator]) -> Vec<Calibration> {
+    debug!("checking {} calibrations with {:?}", calibrations.len(), operators);
+    parallel::filter(calibrations, |calibration| check_calibration(&calibration.result, &calibration.terms, operators))
+}
+
+/// Whether some choice of `operators` between `terms`, evaluated left to right, gives `target`.
+fn check_calibration(target: &BigInt, terms: &[BigInt], operators: &[&'static dyn Operator]) -> bool {
+    find_operators(target, terms, operators).is_some()
+}
+
+/// Finds operators to put between `terms` so they evaluate left to right to `target`.
+/// Works backwards from the last term, un-applying each operator to find what the
+/// terms before it would have to produce.
+fn find_operators(target: &BigInt, terms: &[BigInt], operators: &[&'static dyn Operator]) -> Option<Vec<&'static dyn Operator>> {
+    trace!("{:?} {:?}", target, terms);
+    let (last_term, rest) = terms.split_last()?;
+    if rest.is_empty() {
+        return (target == last_term).then(Vec::new);
     }
-    results
+    operators.iter().find_map(|&operator| {
+        let mut found = match operator.unapply(target, last_term) {
+            Unapplied::Never => None,
+            Unapplied::Exactly(new_target) => find_operators(&new_target, rest, operators),
+            Unapplied::Anything => find_defined(&rest[0], &rest[1..], operators),
+        }?;
+        found.push(operator);
+        Some(found)
+    })
+}
+
+/// Finds operators for `terms` that can be evaluated at all, starting from `value`, for when
+/// any result will do. This goes forwards, since there's no single target to undo.
+fn find_defined(value: &BigInt, terms: &[BigInt], operators: &[&'static dyn Operator]) -> Option<Vec<&'static dyn Operator>> {
+    let Some((next, rest)) = terms.split_first() else {
+        return Some(Vec::new());
+    };
+    operators.iter().find_map(|&operator| {
+        let mut found = find_defined(&operator.apply(value, next)?, rest, operators)?;
+        found.insert(0, operator);
+        Some(found)
+    })
+}
+
+/// How many different choices of operators make `terms` evaluate to `target`. Counted as a
+/// `BigInt` since long lines have more assignments than fit in a `u64`.
+fn count_assignments(target: &BigInt, terms: &[BigInt], operators: &[&dyn Operator]) -> BigInt {
+    let Some((last_term, rest)) = terms.split_last() else {
+        return BigInt::zero();
+    };
+    if rest.is_empty() {
+        return if target == last_term { BigInt::one() } else { BigInt::zero() };
+    }
+    operators.iter()
+        .map(|operator| match operator.unapply(target, last_term) {
+            Unapplied::Never => BigInt::zero(),
+            Unapplied::Exactly(new_target) => count_assignments(&new_target, rest, operators),
+            Unapplied::Anything => count_defined(&rest[0], &rest[1..], operators),
+        })
+        .sum()
+}
+
+/// How many choices of operators for `terms`, starting from `value`, can be evaluated at all.
+fn count_defined(value: &BigInt, terms: &[BigInt], operators: &[&dyn Operator]) -> BigInt {
+    let Some((next, rest)) = terms.split_first() else {
+        return BigInt::one();
+    };
+    operators.iter()
+        .filter_map(|operator| operator.apply(value, next))
+        .map(|value| count_defined(&value, rest, operators))
+        .sum()
+}
+
+/// Shows the working for every calibration that can be solved, optionally counting how
+/// many operator assignments solve it.
+pub fn explain_calibrations(calibrations: &[Calibration], operators: &[&'static dyn Operator], count: bool) -> Vec<Explanation> {
+    parallel::map(calibrations, |calibration| explain_calibration(calibration, operators, count))
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+fn explain_calibration(calibration: &Calibration, operators: &[&'static dyn Operator], count: bool) -> Option<Explanation> {
+    let found = find_operators(&calibration.result, &calibration.terms, operators)?;
+    Some(Explanation {
+        calibration: calibration.clone(),
+        operators: found,
+        assignments: count.then(|| count_assignments(&calibration.result, &calibration.terms, operators)),
+    })
 }
 
-fn check_calibrations_split(calibrations: &Vec<Calibration>) -> Vec<Calibration> {
-    let mut results = vec![];
+fn sum_calibrations(calibrations: &[Calibration]) -> BigInt {
+    let mut sum = BigInt::zero();
     for calibration in calibrations {
-        println!("{:?}", calibration);
-        if check_calibration(&calibration.result, &mut calibration.terms.clone(), Operation::Add, true) 
-            || check_calibration(&calibration.result, &mut calibration.terms.clone(), Operation::Multiply, true)
-            || check_calibration(&calibration.result, &mut calibration.terms.clone(), Operation::Split, true) {
-            results.push(calibration.clone());
+        sum += &calibration.result;
+    }
+    sum
+}
+
+pub struct Calibrations {
+    calibrations: Vec<Calibration>,
+    /// Replaces both parts' operator sets when given.
+    operators: Option<Vec<&'static dyn Operator>>,
+    explain: bool,
+    count: bool,
+}
+
+impl Calibrations {
+    fn operators_or<'a>(&'a self, default: &'a [&'static dyn Operator]) -> &'a [&'static dyn Operator] {
+        self.operators.as_deref().unwrap_or(default)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Calibration {
+    result: BigInt,
+    terms: Vec<BigInt>,
+}
+
+/// One way to solve a calibration, displayed as the equation, like `3267 = 81 * 40 + 27`.
+#[derive(Debug)]
+pub struct Explanation {
+    pub calibration: Calibration,
+    pub operators: Vec<&'static dyn Operator>,
+    /// How many operator assignments solve it in total, when they were counted.
+    pub assignments: Option<BigInt>,
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let terms = &self.calibration.terms;
+        write!(f, "{} = {}", self.calibration.result, terms[0])?;
+        for (operator, term) in self.operators.iter().zip(&terms[1..]) {
+            write!(f, " {} {}", operator.symbol(), term)?;
+        }
+        match &self.assignments {
+            Some(assignments) if assignments.is_one() => write!(f, " (1 way)"),
+            Some(assignments) => write!(f, " ({} ways)", assignments),
+            None => Ok(()),
         }
     }
-    results
 }
 
-fn check_calibration(target: &i64, terms: &mut Vec<i64>, operator: Operation, split: bool) -> bool {
-    println!("{:?} {:?} {:?} {:?}", target, terms, operator, split);
-    let mut terms = terms.clone();
-    let last_term = terms.pop().unwrap();
-    let new_target = if operator == Operation::Add {
-        if *target < last_term {
-            return false;
+/// A binary operator that can sit between two terms of an equation.
+pub trait Operator: Debug + Sync {
+    fn symbol(&self) -> &'static str;
+
+    /// `left` combined with `right`, or `None` if the result is undefined.
+    fn apply(&self, left: &BigInt, right: &BigInt) -> Option<BigInt>;
+
+    /// Un-applies the last term: which `left` makes `apply(left, right)` give `result`.
+    fn unapply(&self, result: &BigInt, right: &BigInt) -> Unapplied;
+}
+
+/// The `left` side an operator needs to give a result, found by `Operator::unapply`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Unapplied {
+    /// No `left` gives the result.
+    Never,
+    /// Only this `left` gives the result.
+    Exactly(BigInt),
+    /// Every `left` gives the result, like anything multiplied by zero.
+    Anything,
+}
+
+impl From<Option<BigInt>> for Unapplied {
+    fn from(left: Option<BigInt>) -> Self {
+        left.map_or(Unapplied::Never, Unapplied::Exactly)
+    }
+}
+
+#[derive(Debug)]
+pub struct Add;
+
+impl Operator for Add {
+    fn symbol(&self) -> &'static str {
+        "+"
+    }
+
+    fn apply(&self, left: &BigInt, right: &BigInt) -> Option<BigInt> {
+        Some(left + right)
+    }
+
+    fn unapply(&self, result: &BigInt, right: &BigInt) -> Unapplied {
+        Unapplied::Exactly(result - right)
+    }
+}
+
+#[derive(Debug)]
+pub struct Multiply;
+
+impl Operator for Multiply {
+    fn symbol(&self) -> &'static str {
+        "*"
+    }
+
+    fn apply(&self, left: &BigInt, right: &BigInt) -> Option<BigInt> {
+        Some(left * right)
+    }
+
+    /// Multiplying by zero gives zero whatever `left` was, so there's no single answer.
+    fn unapply(&self, result: &BigInt, right: &BigInt) -> Unapplied {
+        match (right.is_zero(), result.is_zero()) {
+            (true, true) => Unapplied::Anything,
+            (true, false) => Unapplied::Never,
+            (false, _) => divide_exactly(result, right).into(),
         }
-        target - last_term
-    } else if operator == Operation::Multiply {
-        if target % last_term != 0 {
-            return false;
+    }
+}
+
+/// Joins the digits of both terms, so `12 || 345` is `12345`. Only defined for terms that
+/// aren't negative.
+#[derive(Debug)]
+pub struct Concat;
+
+impl Operator for Concat {
+    fn symbol(&self) -> &'static str {
+        "||"
+    }
+
+    fn apply(&self, left: &BigInt, right: &BigInt) -> Option<BigInt> {
+        if left.is_negative() || right.is_negative() {
+            return None;
         }
-        target / last_term
-    } else if operator == Operation::Split {
-        let last_term_str = last_term.to_string();
-        let target_str = target.to_string();
-        if target_str.len() < last_term_str.len() || !target_str.ends_with(&last_term_str) {
-            return false;
+        Some(left * digit_shift(right) + right)
+    }
+
+    fn unapply(&self, result: &BigInt, right: &BigInt) -> Unapplied {
+        if result.is_negative() || right.is_negative() {
+            return Unapplied::Never;
         }
-        target_str[..target_str.len() - last_term_str.len()].parse::<i64>().unwrap()
-    } else {
-        0
-    }; 
+        let shift = digit_shift(right);
+        (result % &shift == *right).then(|| result / shift).into()
+    }
+}
 
-    if terms.is_empty() {
-        return (new_target == 0 && operator == Operation::Add)
-        || (new_target == 1 && operator == Operation::Multiply)
-        || (new_target == last_term && operator == Operation::Split);
+#[derive(Debug)]
+pub struct Subtract;
+
+impl Operator for Subtract {
+    fn symbol(&self) -> &'static str {
+        "-"
+    }
+
+    fn apply(&self, left: &BigInt, right: &BigInt) -> Option<BigInt> {
+        Some(left - right)
+    }
+
+    fn unapply(&self, result: &BigInt, right: &BigInt) -> Unapplied {
+        Unapplied::Exactly(result + right)
     }
-    
-    if check_calibration(&new_target, &mut terms, Operation::Add, split) { return true; }
-    if check_calibration(&new_target, &mut terms, Operation::Multiply, split) { return true; }
-    if split && check_calibration(&new_target, &mut terms, Operation::Split, split) { return true; }
-    return false;
 }
 
-fn sum_calibrations(calibrations: &Vec<Calibration>) -> i64 {
-    let mut sum = 0;
-    for calibration in calibrations {
-        sum += calibration.result;
+/// Integer division that only applies when it leaves no remainder, which keeps it
+/// reversible.
+#[derive(Debug)]
+pub struct Divide;
+
+impl Operator for Divide {
+    fn symbol(&self) -> &'static str {
+        "/"
+    }
+
+    fn apply(&self, left: &BigInt, right: &BigInt) -> Option<BigInt> {
+        divide_exactly(left, right)
+    }
+
+    fn unapply(&self, result: &BigInt, right: &BigInt) -> Unapplied {
+        (!right.is_zero()).then(|| result * right).into()
     }
-    sum
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct Calibration {
-    result: i64,
-    terms: Vec<i64>,
+fn divide_exactly(dividend: &BigInt, divisor: &BigInt) -> Option<BigInt> {
+    if divisor.is_zero() {
+        return None;
+    }
+    (dividend % divisor).is_zero().then(|| dividend / divisor)
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-enum Operation {
-    Add,
-    Multiply,
-    Split,
+/// The power of ten that shifts a number left by as many digits as `n` has, counted by
+/// multiplying rather than formatting `n`.
+fn digit_shift(n: &BigInt) -> BigInt {
+    let mut shift = BigInt::from(10);
+    while shift <= *n {
+        shift *= 10;
+    }
+    shift
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::{io::Write, vec};
-    use tempfile::NamedTempFile;
+
+    fn big(n: i64) -> BigInt {
+        BigInt::from(n)
+    }
+
+    fn calibration(result: i64, terms: &[i64]) -> Calibration {
+        Calibration { result: big(result), terms: terms.iter().map(|&term| big(term)).collect() }
+    }
+
+    fn check(target: i64, terms: &[i64], operators: &[&'static dyn Operator]) -> bool {
+        let calibration = calibration(target, terms);
+        check_calibration(&calibration.result, &calibration.terms, operators)
+    }
 
     #[test]
     fn it_will_solve_part1() {
-        let input = create_file_input();
-        let formatted = format_input(input);
-        let result = solve_part1(&formatted.clone());
-        assert_eq!(result, 3749);
+        let formatted = format_input(EXAMPLE).unwrap();
+        let result = solve_part1(&formatted);
+        assert_eq!(result, big(3749));
     }
 
     #[test]
     fn it_will_solve_part2() {
-        let input = create_file_input();
-        let formatted = format_input(input);
-        let result = solve_part2(&formatted.clone());
-        assert_eq!(result, 11387);
+        let formatted = format_input(EXAMPLE).unwrap();
+        let result = solve_part2(&formatted);
+        assert_eq!(result, big(11387));
     }
 
     #[test]
     fn test_format_input() {
-        let input = create_file_input();
-        let result = format_input(input);
+        let result = format_input(EXAMPLE).unwrap().calibrations;
         assert_eq!(result.len(), 9);
-        assert_eq!(result[0], Calibration { result: 190, terms: vec![10, 19] });
-        assert_eq!(result[1], Calibration { result: 3267, terms: vec![81, 40, 27] });
-        assert_eq!(result[2], Calibration { result: 83, terms: vec![17, 5] });
-        assert_eq!(result[3], Calibration { result: 156, terms: vec![15, 6] });
-        assert_eq!(result[4], Calibration { result: 7290, terms: vec![6, 8, 6, 15] });
-        assert_eq!(result[5], Calibration { result: 161011, terms: vec![16, 10, 13] });
-        assert_eq!(result[6], Calibration { result: 192, terms: vec![17, 8, 14] });
-        assert_eq!(result[7], Calibration { result: 21037, terms: vec![9, 7, 18, 13] });
-        assert_eq!(result[8], Calibration { result: 292, terms: vec![11, 6, 16, 20] });
+        assert_eq!(result[0], calibration(190, &[10, 19]));
+        assert_eq!(result[1], calibration(3267, &[81, 40, 27]));
+        assert_eq!(result[2], calibration(83, &[17, 5]));
+        assert_eq!(result[3], calibration(156, &[15, 6]));
+        assert_eq!(result[4], calibration(7290, &[6, 8, 6, 15]));
+        assert_eq!(result[5], calibration(161011, &[16, 10, 13]));
+        assert_eq!(result[6], calibration(192, &[17, 8, 14]));
+        assert_eq!(result[7], calibration(21037, &[9, 7, 18, 13]));
+        assert_eq!(result[8], calibration(292, &[11, 6, 16, 20]));
+    }
+
+    #[test]
+    fn it_will_report_malformed_calibrations() {
+        let error = format_input("190: 10 19\n3267 81 40 27\n").err().unwrap();
+        assert_eq!((error.line, error.column), (2, 14));
+
+        let error = format_input("190: 10 1x9\n").err().unwrap();
+        assert_eq!((error.line, error.column), (1, 9));
+
+        let error = format_input("190:\n").err().unwrap();
+        assert_eq!(error.expected, "at least one term");
+    }
+
+    #[test]
+    fn it_will_check() {
+        assert!(!check(190, &[10, 19], &[&Add]));
+        assert!(check(190, &[10, 19], &[&Multiply]));
+        assert!(check(156, &[15, 6], PART2_OPERATORS));
+        assert!(!check(156, &[15, 6], PART1_OPERATORS));
+    }
+
+    #[test]
+    fn it_will_solve_calibrations_with_a_zero_term() {
+        assert!(check(0, &[5, 0], PART1_OPERATORS));
+        assert!(check(10, &[2, 5, 0, 10], PART1_OPERATORS));
+        assert!(!check(10, &[2, 5, 0, 9], PART1_OPERATORS));
+
+        let zero_term = calibration(10, &[2, 5, 0, 10]);
+        assert_eq!(count_assignments(&zero_term.result, &zero_term.terms, PART1_OPERATORS), big(2));
+        let explanation = explain_calibration(&zero_term, PART1_OPERATORS, true).unwrap();
+        assert_eq!(explanation.to_string(), "10 = 2 + 5 * 0 + 10 (2 ways)");
+
+        let many_ways = Explanation { assignments: Some(BigInt::from(u64::MAX) + 1), ..explanation };
+        assert_eq!(many_ways.to_string(), "10 = 2 + 5 * 0 + 10 (18446744073709551616 ways)");
+
+        let dividing_by_zero = calibration(0, &[6, 0, 5, 0]);
+        assert_eq!(count_assignments(&dividing_by_zero.result, &dividing_by_zero.terms, &[&Divide, &Multiply]), big(2));
     }
 
     #[test]
-    fn it_will_check_calibration() {
-        let mut terms = vec![10, 19];
-        let result = check_calibration(&190, &mut terms, Operation::Add, false);
-        assert_eq!(result, false);
-
-        let mut terms = vec![10, 19];
-        let result = check_calibration(&190, &mut terms, Operation::Multiply, false);
-        assert_eq!(result, true);
-    }
-
-    fn create_file_input() -> File {
-        let mut temp_file = NamedTempFile::new().unwrap();
-        writeln!(temp_file, "190: 10 19").unwrap();
-        writeln!(temp_file, "3267: 81 40 27").unwrap();
-        writeln!(temp_file, "83: 17 5").unwrap();
-        writeln!(temp_file, "156: 15 6").unwrap();
-        writeln!(temp_file, "7290: 6 8 6 15").unwrap();
-        writeln!(temp_file, "161011: 16 10 13").unwrap();
-        writeln!(temp_file, "192: 17 8 14").unwrap();
-        writeln!(temp_file, "21037: 9 7 18 13").unwrap();
-        writeln!(temp_file, "292: 11 6 16 20").unwrap();
-        File::open(temp_file.path()).unwrap()
+    fn it_will_handle_targets_too_large_for_an_i64() {
+        let input = format_input("\
+100000000000000000000000000000000000000: 10000000000000000000 10000000000000000000
+123456789012345678901234567890: 123456789012345 678901234567890
+").unwrap();
+        assert_eq!(solve_part1(&input), "100000000000000000000000000000000000000".parse().unwrap());
+        assert_eq!(solve_part2(&input), "100000000000000000000000000000000000000".parse::<BigInt>().unwrap()
+            + "123456789012345678901234567890".parse::<BigInt>().unwrap());
     }
 
+    #[test]
+    fn it_will_explain_solvable_calibrations() {
+        let input = format_input(EXAMPLE).unwrap();
+        let explanations = explain_calibrations(&input.calibrations, PART1_OPERATORS, true);
+        let lines: Vec<String> = explanations.iter().map(|explanation| explanation.to_string()).collect();
+        assert_eq!(lines, vec![
+            "190 = 10 * 19 (1 way)",
+            "3267 = 81 * 40 + 27 (2 ways)",
+            "292 = 11 + 6 * 16 + 20 (1 way)",
+        ]);
+
+        let explanations = explain_calibrations(&input.calibrations, PART2_OPERATORS, false);
+        assert_eq!(explanations.len(), 6);
+        assert_eq!(explanations[3].to_string(), "7290 = 6 * 8 || 6 * 15");
+    }
+
+    #[test]
+    fn it_will_report_workings_only_when_asked() {
+        let mut input = format_input(EXAMPLE).unwrap();
+        assert_eq!(Day07::report(&input, 1), Report::default());
+
+        let mut options = Options::default();
+        options.insert("explain");
+        Day07::configure(&mut input, &options).unwrap();
+        let report = Day07::report(&input, 1).lines;
+        assert_eq!(report[0], "Part 1: 3 calibrations can be solved");
+        assert_eq!(report[1], "  190 = 10 * 19");
+    }
+
+    #[test]
+    fn it_will_undo_what_each_operator_applies() {
+        for operator in parse_operators("+,*,||,-,/").unwrap() {
+            for (left, right) in [(12, 3), (0, 7), (48, 12), (5, 1)] {
+                let (left, right) = (big(left), big(right));
+                if let Some(result) = operator.apply(&left, &right) {
+                    assert_eq!(operator.unapply(&result, &right), Unapplied::Exactly(left.clone()), "{} {} {}", left, operator.symbol(), right);
+                }
+            }
+        }
+        assert_eq!(Concat.apply(&big(12), &big(345)), Some(big(12345)));
+        assert_eq!(Concat.unapply(&big(12345), &big(45)), Unapplied::Exactly(big(123)));
+        assert_eq!(Concat.unapply(&big(12345), &big(5)), Unapplied::Exactly(big(1234)));
+        assert_eq!(Concat.unapply(&big(50), &big(0)), Unapplied::Exactly(big(5)));
+        assert_eq!(Multiply.unapply(&big(0), &big(0)), Unapplied::Anything);
+        assert_eq!(Multiply.unapply(&big(3), &big(0)), Unapplied::Never);
+        assert_eq!(Divide.apply(&big(7), &big(2)), None);
+    }
+
+    #[test]
+    fn it_will_use_a_chosen_operator_set() {
+        let mut input = format_input("5: 10 5\n2: 10 5\n15: 10 5\n").unwrap();
+        let mut options = Options::default();
+        options.insert("operators=-,/");
+        Day07::configure(&mut input, &options).unwrap();
+        assert_eq!(solve_part1(&input), big(7));
+        assert_eq!(solve_part2(&input), big(7));
+
+        let mut options = Options::default();
+        options.insert("operators=+,%");
+        assert!(Day07::configure(&mut input, &options).is_err());
+    }
+
+    const EXAMPLE: &str = "\
+190: 10 19
+3267: 81 40 27
+83: 17 5
+156: 15 6
+7290: 6 8 6 15
+161011: 16 10 13
+192: 17 8 14
+21037: 9 7 18 13
+292: 11 6 16 20
+";
+
 }
\ No newline at end of file
@@ -1,150 +1,129 @@
-use std::fs::File;
-use std::io::{self, BufRead};
 use std::collections::{HashMap, HashSet};
 use std::ops::{Add, Sub};
 
-pub fn solve(input: File) {
-    let formatted = format_input(input);
+use crate::grid::Grid;
+use crate::parse::ParseError;
+use crate::solution::Solution;
 
-    let part1 = solve_part1(&formatted);
-    println!("The answer for part 1 is: {}", part1);
+pub struct Day08;
 
-    let part2 = solve_part2(&formatted);
-    println!("The answer for part 2 is: {}", part2);
+impl Solution for Day08 {
+    const DAY: u32 = 8;
+    const TITLE: &'static str = "Resonant Collinearity";
+
+    type Input = Map;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        format_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        solve_part2(input)
+    }
 }
 
 fn solve_part1(input: &Map) -> i32 {
     let antennas_by_frequency = group_antennas_by_frequency(&input.antennas);
-    let mut antis = Vec::new();
+    let mut points = HashSet::new();
     for (_, antennas) in antennas_by_frequency {
         let antenna_pairs = get_unique_antenna_pairs(&antennas);
-        let antinodes = get_anti_nodes(&antenna_pairs);
-        antis.extend(antinodes);
-    }    
-    let points = get_distinct_points_with_antinodes(&antis);
-    let points_on_map = filter_points_on_map(&points, &input);
-    points_on_map.len() as i32
+        points.extend(get_anti_nodes(&antenna_pairs).into_iter().map(|node| node.point));
+    }
+    points.retain(|point| input.is_point_in_map(*point));
+    points.len() as i32
 }
 
 fn solve_part2(input: &Map) -> i32 {
     let antennas_by_frequency = group_antennas_by_frequency(&input.antennas);
-    let mut antis = Vec::new();
+    let mut points = HashSet::new();
     for (_, antennas) in antennas_by_frequency {
         let antenna_pairs = get_unique_antenna_pairs(&antennas);
-        let antinodes = get_anti_nodes_harmonic(&antenna_pairs, &input);
-        antis.extend(antinodes);
-    }    
-    let points = get_distinct_points_with_antinodes(&antis);
-    let points_on_map = filter_points_on_map(&points, &input);
-    points_on_map.len() as i32
+        points.extend(get_anti_nodes_harmonic(&antenna_pairs, input).into_iter().map(|node| node.point));
+    }
+    points.len() as i32
 }
 
-fn format_input(input: File) -> Map {
-    let reader = io::BufReader::new(input);
-    let mut map = Map {
-        points: Vec::new(),
-        antennas: Vec::new(),
-    };
-    let mut line_num = 0;
-    for line in reader.lines() {
-        let str = line.unwrap();
-        let chars = str.chars().collect::<Vec<char>>();
-        let mut point_num = 0;
-        for c in chars {
-            map.points.push(Point { x: point_num, y: line_num });
-            let c_str = c.to_string();
-            if c_str != "." {
-                map.antennas.push(Antenna {
-                    pos: Point { x: point_num, y: line_num },
-                    freq: c_str,
-                });
-            }
-            point_num += 1;
-        }
-        line_num += 1;
-    }
-    map
+fn format_input(input: &str) -> Result<Map, ParseError> {
+    let grid = Grid::parse(input, Some, "a frequency or '.'")?;
+    let antennas = grid.iter()
+        .filter(|(_, &c)| c != '.')
+        .map(|((row, column), c)| Antenna {
+            pos: Point { x: column as isize, y: row as isize },
+            freq: *c,
+        })
+        .collect();
+    Ok(Map { width: grid.width() as isize, height: grid.height() as isize, antennas })
 }
 
-fn group_antennas_by_frequency(antennas: &Vec<Antenna>) -> HashMap<String, Vec<Antenna>> {
+fn group_antennas_by_frequency(antennas: &[Antenna]) -> HashMap<char, Vec<Antenna>> {
     let mut map = HashMap::new();
     for antenna in antennas {
-        map.entry(antenna.freq.clone()).or_insert(vec![]).push(antenna.clone());
+        map.entry(antenna.freq).or_insert(vec![]).push(*antenna);
     }
     map
 }
 
-fn get_unique_antenna_pairs(antennas: &Vec<Antenna>) -> Vec<AntennaPair> {
-    if antennas.len() < 2 {
-        return Vec::new();
-    }
-    let mut antennas = antennas.clone();
-    let mut pairs = Vec::new();
-    let first = antennas.swap_remove(0);
-    for second in &antennas {
-        pairs.push(AntennaPair {
-            first: first.clone(),
-            second: second.clone(),
-        });
+fn get_unique_antenna_pairs(antennas: &[Antenna]) -> Vec<AntennaPair> {
+    let mut antennas = antennas.to_vec();
+    let mut pairs = Vec::with_capacity(antennas.len() * antennas.len().saturating_sub(1) / 2);
+    while antennas.len() >= 2 {
+        let first = antennas.swap_remove(0);
+        for &second in &antennas {
+            pairs.push(AntennaPair { first, second });
+        }
     }
-    pairs.extend(get_unique_antenna_pairs(&antennas));
     pairs
 }
 
-fn get_anti_nodes(antennas: &Vec<AntennaPair>) -> Vec<AntiNode> {
+fn get_anti_nodes(antennas: &[AntennaPair]) -> Vec<AntiNode> {
     let mut nodes = Vec::new();
     for pair in antennas {
-        let first = pair.first.pos.clone();
-        let second = pair.second.pos.clone();
-        let diff = first.clone() - second.clone();
+        let first = pair.first.pos;
+        let second = pair.second.pos;
+        let diff = first - second;
 
-        nodes.push(AntiNode { antennas: pair.clone(), point: first.clone() + diff.clone() });
-        nodes.push(AntiNode { antennas: pair.clone(), point: second.clone() - diff.clone() });
+        nodes.push(AntiNode { antennas: *pair, point: first + diff });
+        nodes.push(AntiNode { antennas: *pair, point: second - diff });
     }
     nodes
 }
 
-fn get_anti_nodes_harmonic(antennas: &Vec<AntennaPair>, map: &Map) -> Vec<AntiNode> {
+fn get_anti_nodes_harmonic(antennas: &[AntennaPair], map: &Map) -> Vec<AntiNode> {
     let mut nodes = Vec::new();
     for pair in antennas {
-        let first = pair.first.pos.clone();
-        let second = pair.second.pos.clone();
-        let diff = first.clone() - second.clone();
-        let mut new_point = first.clone();
+        let first = pair.first.pos;
+        let second = pair.second.pos;
+        let diff = first - second;
+        let mut new_point = first;
         while map.is_point_in_map(new_point) {
-            nodes.push(AntiNode { antennas: pair.clone(), point: new_point });
-            new_point = new_point + diff.clone();
+            nodes.push(AntiNode { antennas: *pair, point: new_point });
+            new_point = new_point + diff;
         }
-        new_point = second.clone();
+        new_point = second;
         while map.is_point_in_map(new_point) {
-            nodes.push(AntiNode { antennas: pair.clone(), point: new_point });
-            new_point = new_point - diff.clone();
+            nodes.push(AntiNode { antennas: *pair, point: new_point });
+            new_point = new_point - diff;
         }
     }
     nodes
 }
 
-fn get_distinct_points_with_antinodes(antinodes: &Vec<AntiNode>) -> Vec<Point> {
-    let mut points = HashSet::new();
-    for node in antinodes {
-        points.insert(node.point.clone());
-    }
-    points.into_iter().collect()
-}
-
-fn filter_points_on_map(points: &Vec<Point>, map: &Map) -> Vec<Point> {
-    points.iter().filter(|point| map.is_point_in_map(**point)).cloned().collect()
-}
-
 #[derive(Clone, Debug, PartialEq, Eq)]
-struct Map {
-    points: Vec<Point>,
+pub struct Map {
+    width: isize,
+    height: isize,
     antennas: Vec<Antenna>,
 }
 
 impl Map {
     fn is_point_in_map(&self, point: Point) -> bool {
-        self.points.contains(&point)
+        (0..self.width).contains(&point.x) && (0..self.height).contains(&point.y)
     }
 }
 
@@ -173,13 +152,13 @@ impl Add for Point {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 struct Antenna {
     pos: Point,
-    freq: String,
+    freq: char,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 struct AntennaPair {
     first: Antenna,
     second: Antenna,
@@ -194,50 +173,47 @@ struct AntiNode {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::{io::Write, vec};
-    use tempfile::NamedTempFile;
+    use std::vec;
 
     #[test]
     fn it_will_solve_part1() {
-        let input = create_file_input();
-        let formatted = format_input(input);
+        let formatted = format_input(EXAMPLE).unwrap();
         let part1 = solve_part1(&formatted);
         assert_eq!(part1, 14);
     }
 
     #[test]
     fn it_will_solve_part2() {
-        let input = create_file_input();
-        let formatted = format_input(input);
+        let formatted = format_input(EXAMPLE).unwrap();
         let part2 = solve_part2(&formatted);
         assert_eq!(part2, 34);
     }
 
     #[test]
     fn it_will_group_antennas_by_frequency() {
-        let antennas = vec![Antenna { pos: Point { x: 0, y: 0 }, freq: "1".to_string() }, Antenna { pos: Point { x: 1, y: 0 }, freq: "1".to_string() }, Antenna { pos: Point { x: 0, y: 1 }, freq: "2".to_string() }];
+        let antennas = vec![Antenna { pos: Point { x: 0, y: 0 }, freq: '1' }, Antenna { pos: Point { x: 1, y: 0 }, freq: '1' }, Antenna { pos: Point { x: 0, y: 1 }, freq: '2' }];
         let grouped = group_antennas_by_frequency(&antennas);
         assert_eq!(grouped.len(), 2);
-        assert_eq!(grouped.get("1").unwrap().len(), 2);
-        assert_eq!(grouped.get("2").unwrap().len(), 1);
+        assert_eq!(grouped.get(&'1').unwrap().len(), 2);
+        assert_eq!(grouped.get(&'2').unwrap().len(), 1);
     }
 
     #[test]
     fn it_will_get_antenna_pairs() {
-        let antennas = vec![Antenna { pos: Point { x: 0, y: 0 }, freq: "1".to_string() }, Antenna { pos: Point { x: 1, y: 0 }, freq: "2".to_string() }, Antenna { pos: Point { x: 0, y: 1 }, freq: "3".to_string() }];
+        let antennas = vec![Antenna { pos: Point { x: 0, y: 0 }, freq: '1' }, Antenna { pos: Point { x: 1, y: 0 }, freq: '2' }, Antenna { pos: Point { x: 0, y: 1 }, freq: '3' }];
         let pairs = get_unique_antenna_pairs(&antennas);
         assert_eq!(pairs.len(), 3);
-        assert_eq!(pairs[0].first.freq, "1");
-        assert_eq!(pairs[0].second.freq, "3");
-        assert_eq!(pairs[1].first.freq, "1");
-        assert_eq!(pairs[1].second.freq, "2");
-        assert_eq!(pairs[2].first.freq, "3");
-        assert_eq!(pairs[2].second.freq, "2");
+        assert_eq!(pairs[0].first.freq, '1');
+        assert_eq!(pairs[0].second.freq, '3');
+        assert_eq!(pairs[1].first.freq, '1');
+        assert_eq!(pairs[1].second.freq, '2');
+        assert_eq!(pairs[2].first.freq, '3');
+        assert_eq!(pairs[2].second.freq, '2');
     }
 
     #[test]
     fn it_will_get_anti_nodes() {
-        let pairs = vec![AntennaPair { first: Antenna { pos: Point { x: 5, y: 5 }, freq: "1".to_string() }, second: Antenna { pos: Point { x: 3, y: 4 }, freq: "1".to_string() } }];
+        let pairs = vec![AntennaPair { first: Antenna { pos: Point { x: 5, y: 5 }, freq: '1' }, second: Antenna { pos: Point { x: 3, y: 4 }, freq: '1' } }];
         let nodes = get_anti_nodes(&pairs);
         assert_eq!(nodes.len(), 2);
         println!("{:?}", nodes);
@@ -245,19 +221,46 @@ mod tests {
         assert_eq!(nodes[1].point, Point { x: 1, y: 3 });
     }
 
-    fn create_file_input() -> File {
-        let mut temp_file = NamedTempFile::new().unwrap();
-        writeln!(temp_file, "............").unwrap();
-        writeln!(temp_file, "........0...").unwrap();
-        writeln!(temp_file, ".....0......").unwrap();
-        writeln!(temp_file, ".......0....").unwrap();
-        writeln!(temp_file, "....0.......").unwrap();
-        writeln!(temp_file, "......A.....").unwrap();
-        writeln!(temp_file, "............").unwrap();
-        writeln!(temp_file, "........A...").unwrap();
-        writeln!(temp_file, ".........A..").unwrap();
-        writeln!(temp_file, "............").unwrap();
-        writeln!(temp_file, "............").unwrap();
-        File::open(temp_file.path()).unwrap()
+    #[test]
+    fn it_will_find_the_antennas_and_bounds() {
+        let map = format_input("..a\n.b.\n").unwrap();
+        assert_eq!((map.width, map.height), (3, 2));
+        assert_eq!(map.antennas, vec![
+            Antenna { pos: Point { x: 2, y: 0 }, freq: 'a' },
+            Antenna { pos: Point { x: 1, y: 1 }, freq: 'b' },
+        ]);
+        assert!(map.is_point_in_map(Point { x: 2, y: 1 }));
+        assert!(!map.is_point_in_map(Point { x: 3, y: 1 }));
+        assert!(!map.is_point_in_map(Point { x: 0, y: -1 }));
     }
+
+    #[test]
+    fn it_will_handle_a_large_map() {
+        let size = 1000;
+        let mut rows = vec![".".repeat(size); size];
+        rows[0].replace_range(0..1, "a");
+        rows[1].replace_range(1..2, "a");
+        rows[0].replace_range(size - 1..size, "b");
+        rows[2].replace_range(size - 1..size, "b");
+        let map = format_input(&rows.join("\n")).unwrap();
+        // Each pair has one antinode inside the map. The harmonics of the a pair run down
+        // the main diagonal, and those of the b pair down every other row of the last column.
+        assert_eq!(solve_part1(&map), 2);
+        assert_eq!(solve_part2(&map), size as i32 + size as i32 / 2);
+    }
+
+    const EXAMPLE: &str = "\
+............
+........0...
+.....0......
+.......0....
+....0.......
+......A.....
+............
+............
+........A...
+.........A..
+............
+............
+";
 }
@@ -1,274 +1,725 @@
-use std::fs::File;
-use std::io::{self, Read};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::fmt;
+use std::iter;
+use std::str::FromStr;
+
+use log::debug;
 use num_bigint::BigInt;
 
-pub fn solve(input: File) {
-    let formatted = format_input(input);
+use crate::options::{Destination, OptionError, Options, Report};
+use crate::parse::{self, ParseError};
+use crate::solution::Solution;
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    const DAY: u32 = 9;
+    const TITLE: &'static str = "Disk Fragmenter";
 
-    let part1 = solve_part1(&formatted);
-    println!("The answer for part 1 is: {}", part1);
+    type Input = Disk;
+    type Output1 = BigInt;
+    type Output2 = BigInt;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        format_input(input).map(|map| Disk { map, strategy: None, stats: false, replay: None, replay_moves: None, colour: false })
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        solve(&input.map, input.strategy_for(1))
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        solve(&input.map, input.strategy_for(2))
+    }
 
-    let part2 = solve_part2(&formatted);
-    println!("The answer for part 2 is: {}", part2);
+    fn configure(input: &mut Self::Input, options: &Options) -> Result<(), OptionError> {
+        options.allow_only(&["strategy", "stats", "replay", "moves", "colour"])?;
+        input.strategy = options.parse("strategy")?;
+        input.stats = options.is_set("stats");
+        input.replay = options.destination("replay");
+        input.replay_moves = options.parse("moves")?;
+        input.colour = options.is_set("colour");
+        Ok(())
+    }
+
+    /// Prints statistics for each part's compaction, and replays the moves behind part 2
+    /// since that's the compaction that is hard to follow.
+    fn report(input: &Self::Input, part: u32) -> Report {
+        let mut report = Report::default();
+        if !input.stats && (input.replay.is_none() || part != 2) {
+            return report;
+        }
+        let strategy = input.strategy_for(part);
+        let mut compacted = input.map.clone();
+        let moves = compacted.compact(strategy);
+        if input.stats {
+            report.lines.push(format!("Part {}: {}", part, Stats::of(strategy, &moves, &compacted)));
+        }
+        if let Some(replay) = input.replay.as_ref().filter(|_| part == 2) {
+            let replayed = input.map.replay(&moves, input.colour, input.replay_moves);
+            report.send(replay, replayed, &format!("the replay of {} file moves", moves.len()));
+        }
+        report
+    }
 }
 
-fn solve_part1(input: &DiskMap) -> BigInt {
-    let mut map = input.clone();
-    map.sort_blocks();
-    map.checksum()
+/// The parsed disk along with how a run wants it compacted and shown.
+pub struct Disk {
+    map: DiskMap,
+    /// Replaces both parts' strategies when given.
+    strategy: Option<Strategy>,
+    stats: bool,
+    /// Where to send the step by step replay of part 2.
+    replay: Option<Destination>,
+    /// Stops the replay after this many moves, since each one draws the whole disk.
+    replay_moves: Option<usize>,
+    colour: bool,
 }
 
-fn solve_part2(input: &DiskMap) -> BigInt {
+impl Disk {
+    fn strategy_for(&self, part: u32) -> Strategy {
+        self.strategy.unwrap_or(if part == 1 { Strategy::Blocks } else { Strategy::Files(Fit::First) })
+    }
+}
+
+fn solve(input: &DiskMap, strategy: Strategy) -> BigInt {
     let mut map = input.clone();
-    map.sort_files();
+    map.compact(strategy);
     map.checksum()
 }
 
-fn format_input(input: File) -> DiskMap {
-    println!("FORMATTING");
-    let mut buf = String::new();
-    let mut reader = io::BufReader::new(input);
-    reader.read_to_string(&mut buf).unwrap();
-    DiskMap::new(&buf.trim().to_string())
+/// How to compact a disk, chosen with `--option strategy=<name>`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Strategy {
+    /// Fills free space block by block from the end of the disk, splitting files.
+    Blocks,
+    /// Moves whole files, highest id first, into free space picked by the `Fit`.
+    Files(Fit),
+    /// Slides every file left, in order, until there are no gaps between them.
+    Defragment,
 }
 
+/// Which of the free spaces that can hold a file it moves into.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Fit {
+    /// The leftmost.
+    First,
+    /// The smallest, leaving the least space over.
+    Best,
+    /// The largest, leaving the most space over.
+    Worst,
+}
 
+impl FromStr for Strategy {
+    type Err = OptionError;
 
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "blocks" => Ok(Strategy::Blocks),
+            "first-fit" => Ok(Strategy::Files(Fit::First)),
+            "best-fit" => Ok(Strategy::Files(Fit::Best)),
+            "worst-fit" => Ok(Strategy::Files(Fit::Worst)),
+            "defragment" => Ok(Strategy::Defragment),
+            _ => Err(OptionError(format!(
+                "Unknown strategy '{}', expected blocks, first-fit, best-fit, worst-fit or defragment", name
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Strategy::Blocks => "blocks",
+            Strategy::Files(Fit::First) => "first-fit",
+            Strategy::Files(Fit::Best) => "best-fit",
+            Strategy::Files(Fit::Worst) => "worst-fit",
+            Strategy::Defragment => "defragment",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// What a compaction did and how fragmented it left the disk.
 #[derive(Clone, PartialEq, Eq, Debug)]
-struct DiskMap {
+struct Stats {
+    strategy: Strategy,
+    moves: usize,
+    blocks_moved: usize,
+    /// Free spans with files after them, which compaction would ideally close up.
+    gaps: usize,
+    /// Files stored in more than one span.
+    split_files: usize,
+    checksum: BigInt,
+}
+
+impl Stats {
+    fn of(strategy: Strategy, moves: &[FileMove], compacted: &DiskMap) -> Self {
+        let last_file = compacted.files().last().map_or(0, |file| file.start);
+        let mut spans_per_file = HashMap::new();
+        for file in compacted.files() {
+            *spans_per_file.entry(file.block_type).or_insert(0) += 1;
+        }
+        Stats {
+            strategy,
+            moves: moves.len(),
+            blocks_moved: moves.iter().map(|file_move| file_move.file.blocks).sum(),
+            gaps: compacted.spans.iter().filter(|span| span.block_type == BlockType::Free && span.start < last_file).count(),
+            split_files: spans_per_file.values().filter(|&&spans| spans > 1).count(),
+            checksum: compacted.checksum(),
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} made {} moves of {} blocks, leaving {} gaps and {} split files, checksum {}",
+            self.strategy, self.moves, self.blocks_moved, self.gaps, self.split_files, self.checksum
+        )
+    }
+}
+
+fn format_input(input: &str) -> Result<DiskMap, ParseError> {
+    debug!("formatting");
+    let line = parse::lines(input).next().unwrap_or(parse::Line { number: 1, text: "" });
+    if let Some((i, c)) = line.text.trim_end().char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+        return Err(line.error_at(line.text[..i].chars().count() + 1, format!("a digit, found '{}'", c)));
+    }
+    if let Some(extra) = parse::lines(input).skip(1).find(|line| !line.text.trim().is_empty()) {
+        return Err(extra.error_at(1, "the disk map on a single line"));
+    }
+    Ok(DiskMap::new(line.text.trim_end()))
+}
+
+/// A disk as runs of blocks. Each span is a whole file, or a fragment of one, or a
+/// stretch of free space, so compaction moves spans around instead of single blocks.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DiskMap {
     raw: String,
-    map: Vec<BlockType>
+    spans: Vec<Span>,
 }
 
 impl DiskMap {
-    fn new(raw: &String) -> Self {
-        println!("MAPPING");
-        let mut map = Vec::new();
-        let mut id = 0;
-        for i in 0..raw.len() {
-            let c = raw.chars().nth(i).unwrap();
-            if i % 2 != 0{
-                map.extend(BlockType::Free.repeat(c.to_string().parse::<usize>().unwrap()));
-            } else {
-                map.extend(BlockType::File(id).repeat(c.to_string().parse::<usize>().unwrap()));
-                id += 1;
+    fn new(raw: &str) -> Self {
+        debug!("mapping");
+        let spans = raw.chars().enumerate().map(|(i, c)| {
+            let block_type = if i % 2 == 0 { BlockType::File(i / 2) } else { BlockType::Free };
+            Span { block_type, blocks: c.to_digit(10).unwrap() as usize, start: 0 }
+        });
+        Self::from_spans(raw, spans)
+    }
+
+    /// Lays `spans` out end to end, joining neighbours of the same type.
+    fn from_spans(raw: &str, spans: impl IntoIterator<Item = Span>) -> Self {
+        let mut joined: Vec<Span> = Vec::new();
+        let mut start = 0;
+        for span in spans.into_iter().filter(|span| span.blocks > 0) {
+            match joined.last_mut() {
+                Some(last) if last.block_type == span.block_type => last.blocks += span.blocks,
+                _ => joined.push(Span { start, ..span }),
             }
+            start += span.blocks;
+        }
+        Self { raw: raw.to_string(), spans: joined }
+    }
+
+    fn len(&self) -> usize {
+        self.spans.last().map_or(0, |span| span.start + span.blocks)
+    }
+
+    /// Compacts the disk with `strategy`, returning the moves in the order they were made.
+    fn compact(&mut self, strategy: Strategy) -> Vec<FileMove> {
+        match strategy {
+            Strategy::Blocks => self.sort_blocks(),
+            Strategy::Files(fit) => self.sort_files(fit),
+            Strategy::Defragment => self.defragment(),
         }
-        Self { raw: raw.to_string(), map: map }
     }
 
-    fn sort_blocks(&mut self) {
-        println!("SORTING");
-        let mut blocks = self.map.clone();
-        let mut free = Vec::new();
-        while blocks.contains(&BlockType::Free) {
-            while blocks.last().unwrap() == &BlockType::Free {
-                blocks.pop();
-                free.push(BlockType::Free);
+    /// Moves blocks one at a time from the end of the disk into the leftmost free space,
+    /// splitting files where they don't fit. Each piece of a file moved at once is one move.
+    fn sort_blocks(&mut self) -> Vec<FileMove> {
+        debug!("sorting blocks");
+        let mut spans: VecDeque<Span> = self.spans.iter().copied().collect();
+        let mut compacted = Vec::new();
+        let mut moves = Vec::new();
+        while let Some(span) = spans.pop_front() {
+            if span.block_type != BlockType::Free {
+                compacted.push(span);
+                continue;
+            }
+            let mut space = span.blocks;
+            while space > 0 {
+                while spans.back().is_some_and(|last| last.block_type == BlockType::Free) {
+                    spans.pop_back();
+                }
+                let Some(last) = spans.back_mut() else { break };
+                let moved = space.min(last.blocks);
+                let piece = Span { blocks: moved, start: last.start + last.blocks - moved, ..*last };
+                moves.push(FileMove { file: piece, to: span.start + span.blocks - space });
+                compacted.push(piece);
+                last.blocks -= moved;
+                space -= moved;
+                if last.blocks == 0 {
+                    spans.pop_back();
+                }
             }
-            let index = blocks.iter().position(|&b| b == BlockType::Free).unwrap();
-            blocks.swap_remove(index);
-            free.push(BlockType::Free);
         }
-        blocks.extend(free);
-        self.map = blocks;
+        let used: usize = compacted.iter().map(|span| span.blocks).sum();
+        compacted.push(Span { block_type: BlockType::Free, blocks: self.len() - used, start: 0 });
+        *self = Self::from_spans(&self.raw, compacted);
+        moves
     }
 
+    /// Adds up each block's position times its file id, a whole span at a time.
     fn checksum(&self) -> BigInt {
-        println!("CHECKSUM");
+        debug!("checksum");
         let mut checksum = BigInt::from(0);
-        for (i, block) in self.map.iter().enumerate() {
-            if let BlockType::File(id) = block {
-                checksum += BigInt::from(*id) * BigInt::from(i);
+        for span in &self.spans {
+            if let BlockType::File(id) = span.block_type {
+                let positions = BigInt::from(span.blocks) * (2 * span.start + span.blocks - 1) / 2;
+                checksum += BigInt::from(id) * positions;
             }
         }
         checksum
     }
 
-    fn group_blocks_by_id(&self) -> Vec<AFile> {
-        println!("GROUPING");
-        let mut files = Vec::new();
-        let mut current_file: Option<AFile> = None;
-        
-        for (i, block) in self.map.iter().enumerate() {
-            match (&current_file, block) {
-                (None, _) => {
-                    current_file = Some(AFile {
-                        block_type: *block,
-                        blocks: 1,
-                        index_of_first_block: i,
-                    });
-                },
-                (Some(file), block) if file.block_type == *block => {
-                    current_file = Some(AFile {
-                        block_type: file.block_type,
-                        blocks: file.blocks + 1,
-                        index_of_first_block: file.index_of_first_block,
-                    });
-                },
-                (Some(file), _) => {
-                    files.push(*file);
-                    current_file = Some(AFile {
-                        block_type: *block,
-                        blocks: 1,
-                        index_of_first_block: i,
-                    });
-                }
+    fn files(&self) -> impl DoubleEndedIterator<Item = Span> + '_ {
+        self.spans.iter().copied().filter(|span| span.block_type != BlockType::Free)
+    }
+
+    /// Moves each whole file, highest id first, into a free space before it that can hold
+    /// it, chosen by `fit`.
+    fn sort_files(&mut self, fit: Fit) -> Vec<FileMove> {
+        debug!("sorting files");
+        let mut free = FreeSpace::new(&self.spans);
+        let mut files: Vec<Span> = self.files().collect();
+        let mut moves = Vec::new();
+        for file in files.iter_mut().rev() {
+            if let Some(start) = free.take(fit, file.blocks, file.start) {
+                moves.push(FileMove { file: *file, to: start });
+                file.start = start;
             }
         }
-        if let Some(file) = current_file {
-            files.push(file);
+        files.sort_by_key(|file| file.start);
+
+        let mut spans = Vec::new();
+        let mut end = 0;
+        for file in files {
+            spans.push(Span { block_type: BlockType::Free, blocks: file.start - end, start: end });
+            end = file.start + file.blocks;
+            spans.push(file);
         }
-        
-        files
+        spans.push(Span { block_type: BlockType::Free, blocks: self.len() - end, start: end });
+        *self = Self::from_spans(&self.raw, spans);
+        moves
     }
 
-    fn group_files_by_type(&self, files: &Vec<AFile>) -> (Vec<AFile>, Vec<AFile>) {
-        let files = files.clone();
-        let mut occupied = Vec::new();
-        let mut free = Vec::new();
-        for file in files {
-            if file.block_type == BlockType::Free {
-                free.push(file);
-            } else {
-                occupied.push(file);
+    /// Slides each file left, in order, so all the free space ends up together at the end.
+    fn defragment(&mut self) -> Vec<FileMove> {
+        debug!("defragmenting");
+        let mut spans = Vec::new();
+        let mut moves = Vec::new();
+        let mut end = 0;
+        for file in self.files() {
+            if file.start != end {
+                moves.push(FileMove { file, to: end });
             }
+            spans.push(Span { start: end, ..file });
+            end += file.blocks;
         }
-        (occupied, free)
+        spans.push(Span { block_type: BlockType::Free, blocks: self.len() - end, start: end });
+        *self = Self::from_spans(&self.raw, spans);
+        moves
     }
 
-    #[allow(dead_code)]
-    fn ungroup_blocks_into_map(&mut self, files: &Vec<AFile>) {
-        let mut map = Vec::new();
-        for file in files {
-            map.extend(file.block_type.repeat(file.blocks))
+    /// Moves a file, or a piece of one, to start at `to`, leaving free space behind. The
+    /// destination may overlap where the file was.
+    fn move_file(&mut self, file_move: &FileMove) {
+        let FileMove { file, to } = *file_move;
+        self.fill(file.start, file.blocks, BlockType::Free);
+        self.fill(to, file.blocks, file.block_type);
+    }
+
+    /// Sets `blocks` blocks from `start` onwards to `block_type`, splitting spans as needed.
+    fn fill(&mut self, start: usize, blocks: usize, block_type: BlockType) {
+        let end = start + blocks;
+        let mut spans = Vec::with_capacity(self.spans.len() + 2);
+        for &span in &self.spans {
+            let span_end = span.start + span.blocks;
+            let (from, to) = (start.clamp(span.start, span_end), end.clamp(span.start, span_end));
+            spans.push(Span { blocks: from - span.start, ..span });
+            spans.push(Span { block_type, blocks: to - from, start: from });
+            spans.push(Span { blocks: span_end - to, ..span });
+        }
+        *self = Self::from_spans(&self.raw, spans);
+    }
+
+    /// Describes each move with the layout after it, starting from this map. Each layout is
+    /// the one the next move starts from. `limit` stops after that many moves.
+    fn replay(&self, moves: &[FileMove], colour: bool, limit: Option<usize>) -> Vec<String> {
+        let mut map = self.clone();
+        let mut lines = vec![format!("start:  {}", map.render(colour))];
+        let shown = limit.unwrap_or(moves.len()).min(moves.len());
+        for file_move in &moves[..shown] {
+            lines.push(file_move.to_string());
+            map.move_file(file_move);
+            lines.push(format!("  after:  {}", map.render(colour)));
         }
-        self.map = map;
+        if shown < moves.len() {
+            lines.push(format!("stopped after {} of {} moves", shown, moves.len()));
+        }
+        lines
     }
 
-    fn sort_files(&mut self) {
-        println!("SORTING");
-        let (mut occupied, mut free) = self.group_files_by_type(&self.group_blocks_by_id());
-        occupied.reverse();
-        for i in 0..occupied.len() {
-            for j in 0..free.len() {
-                if self.try_swap_file_with_free(&occupied[i], &free[j]) {
-                    break;
+    /// Writes the disk back in the dense format `new` reads: digits alternating between file
+    /// and free lengths. The format can't name file ids, so files are numbered in the order
+    /// they appear when read back, and spans longer than 9 blocks are split with zero
+    /// length spans in between. Reading the result gives the same layout of used and free
+    /// blocks.
+    pub fn encode(&self) -> String {
+        let mut encoded = String::new();
+        let mut next_is_file = true;
+        for span in &self.spans {
+            let is_file = span.block_type != BlockType::Free;
+            let mut left = span.blocks;
+            while left > 0 {
+                if is_file != next_is_file {
+                    encoded.push('0');
                 }
+                let blocks = left.min(9);
+                encoded.push(char::from(b'0' + blocks as u8));
+                next_is_file = !is_file;
+                left -= blocks;
             }
-            (_, free) = self.group_files_by_type(&self.group_blocks_by_id());
         }
+        encoded
     }
 
-    fn try_swap_file_with_free(&mut self, file: &AFile, free: &AFile) -> bool {
-        if free.blocks < file.blocks || file.index_of_first_block < free.index_of_first_block {
-            return false;
-        }
-        let mut map = self.map.clone();
-        for i in 0..file.blocks {
-            map.swap(file.index_of_first_block + i, free.index_of_first_block + i);
+    /// Draws the disk in the puzzle's notation, one character per block with `.` for free
+    /// space. Ids past 9 wrap to their last digit; with `colour` each run of ten ids gets its
+    /// own terminal colour so files 3 and 13 can still be told apart.
+    fn render(&self, colour: bool) -> String {
+        let mut rendered = String::with_capacity(self.len());
+        for span in &self.spans {
+            let BlockType::File(id) = span.block_type else {
+                rendered.extend(iter::repeat_n('.', span.blocks));
+                continue;
+            };
+            let digit = char::from(b'0' + (id % 10) as u8);
+            if colour {
+                rendered.push_str(&format!("\x1b[{}m", 31 + id / 10 % 6));
+            }
+            rendered.extend(iter::repeat_n(digit, span.blocks));
+            if colour {
+                rendered.push_str("\x1b[0m");
+            }
         }
-        self.map = map;
-        true
+        rendered
     }
 }
 
-#[derive(Clone, PartialEq, Eq, Debug, Copy)]
-enum BlockType {
-    File(i32),
-    Free,
+impl fmt::Display for DiskMap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render(false))
+    }
+}
+
+/// A file, or for `sort_blocks` a piece of one, moved to start at block `to`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct FileMove {
+    file: Span,
+    to: usize,
 }
 
-impl BlockType {
-    fn repeat(&self, n: usize) -> Vec<BlockType> {
-        let mut result = Vec::new();
-        for _i in 0..n {
-            result.push(self.clone());
+impl fmt::Display for FileMove {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let BlockType::File(id) = self.file.block_type else { unreachable!("only files are moved") };
+        write!(f, "move file {} ({} blocks) from {} to {}", id, self.file.blocks, self.file.start, self.to)
+    }
+}
+
+/// The free spans of a disk, bucketed by size with each bucket ordered by position, so
+/// the space that fits a file is found without scanning the disk.
+struct FreeSpace {
+    by_size: Vec<BinaryHeap<Reverse<usize>>>,
+}
+
+impl FreeSpace {
+    fn new(spans: &[Span]) -> Self {
+        let free = spans.iter().filter(|span| span.block_type == BlockType::Free);
+        let largest = free.clone().map(|span| span.blocks).max().unwrap_or(0);
+        let mut by_size = vec![BinaryHeap::new(); largest + 1];
+        for span in free {
+            by_size[span.blocks].push(Reverse(span.start));
         }
-        result
+        FreeSpace { by_size }
     }
+
+    /// Claims `blocks` blocks at the start of a free span that is big enough and starts
+    /// before `before`, picked by `fit`, returning where that is. Spans of the same size
+    /// are taken leftmost first.
+    fn take(&mut self, fit: Fit, blocks: usize, before: usize) -> Option<usize> {
+        let mut candidates = (blocks..self.by_size.len())
+            .filter_map(|size| self.by_size[size].peek().map(|&Reverse(start)| (size, start)))
+            .filter(|&(_, start)| start < before);
+        let (size, start) = match fit {
+            Fit::First => candidates.min_by_key(|&(_, start)| start),
+            Fit::Best => candidates.next(),
+            Fit::Worst => candidates.next_back(),
+        }?;
+        self.by_size[size].pop();
+        if size > blocks {
+            self.by_size[size - blocks].push(Reverse(start + blocks));
+        }
+        Some(start)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Copy)]
+enum BlockType {
+    File(usize),
+    Free,
 }
 
+/// A run of `blocks` blocks of the same type, beginning at block `start`.
 #[derive(Clone, PartialEq, Eq, Debug, Copy)]
-struct AFile {
+struct Span {
     block_type: BlockType,
     blocks: usize,
-    index_of_first_block: usize,
+    start: usize,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use tempfile::NamedTempFile;
-    use std::io::Write;
 
     #[test]
     fn it_will_solve_part1() {
-        let input = create_file_input();
-        let formatted = format_input(input);
-        let part1 = solve_part1(&formatted);
+        let formatted = format_input(EXAMPLE).unwrap();
+        let part1 = solve(&formatted, Strategy::Blocks);
         assert_eq!(part1, BigInt::from(1928));
     }
 
     #[test]
     fn it_will_solve_part2() {
-        let input = create_file_input();
-        let formatted = format_input(input);
-        let part2 = solve_part2(&formatted);
+        let formatted = format_input(EXAMPLE).unwrap();
+        let part2 = solve(&formatted, Strategy::Files(Fit::First));
         assert_eq!(part2, BigInt::from(2858));
     }
 
     #[test]
     fn it_will_format_the_input() {
-        let input = create_file_input();
-        let formatted = format_input(input);
+        let formatted = format_input(EXAMPLE).unwrap();
         assert_eq!(formatted.raw, "2333133121414131402");
     }
 
     #[test]
-    fn it_will_group_blocks_by_id() {
-        let mut temp_file = NamedTempFile::new().unwrap();
-        writeln!(temp_file, "12345").unwrap();
-        let formatted = format_input(temp_file.reopen().unwrap());
-        let grouped = formatted.group_blocks_by_id();
-        assert_eq!(grouped, vec![
-            AFile { block_type: BlockType::File(0), blocks: 1, index_of_first_block: 0 },
-            AFile { block_type: BlockType::Free, blocks: 2, index_of_first_block: 1 },
-            AFile { block_type: BlockType::File(1), blocks: 3, index_of_first_block: 3 },
-            AFile { block_type: BlockType::Free, blocks: 4, index_of_first_block: 6 },
-            AFile { block_type: BlockType::File(2), blocks: 5, index_of_first_block: 10 },
+    fn it_will_report_a_character_that_is_not_a_digit() {
+        let error = format_input("2333x33\n").err().unwrap();
+        assert_eq!((error.line, error.column), (1, 5));
+
+        let error = format_input("123\n\n456\n").err().unwrap();
+        assert_eq!((error.line, error.column), (3, 1));
+        assert!(format_input("123\n\n  \n").is_ok());
+    }
+
+    fn span(block_type: BlockType, blocks: usize, start: usize) -> Span {
+        Span { block_type, blocks, start }
+    }
+
+    #[test]
+    fn it_will_map_the_disk_into_spans() {
+        let formatted = format_input("12345\n").unwrap();
+        assert_eq!(formatted.spans, vec![
+            span(BlockType::File(0), 1, 0),
+            span(BlockType::Free, 2, 1),
+            span(BlockType::File(1), 3, 3),
+            span(BlockType::Free, 4, 6),
+            span(BlockType::File(2), 5, 10),
+        ]);
+
+        let formatted = format_input("11021\n").unwrap();
+        assert_eq!(formatted.spans[1], span(BlockType::Free, 3, 1));
+    }
+
+    #[test]
+    fn it_will_sort_blocks() {
+        let mut formatted = format_input("12345\n").unwrap();
+        formatted.sort_blocks();
+        assert_eq!(formatted.spans, vec![
+            span(BlockType::File(0), 1, 0),
+            span(BlockType::File(2), 2, 1),
+            span(BlockType::File(1), 3, 3),
+            span(BlockType::File(2), 3, 6),
+            span(BlockType::Free, 6, 9),
         ]);
     }
 
     #[test]
     fn it_will_sort_files() {
         let files = vec![
-            AFile { block_type: BlockType::File(0), blocks: 1, index_of_first_block: 0 },
-            AFile { block_type: BlockType::Free, blocks: 2, index_of_first_block: 1 },
-            AFile { block_type: BlockType::File(1), blocks: 3, index_of_first_block: 3 },
-            AFile { block_type: BlockType::File(2), blocks: 2, index_of_first_block: 6 },
+            span(BlockType::File(0), 1, 0),
+            span(BlockType::Free, 2, 0),
+            span(BlockType::File(1), 3, 0),
+            span(BlockType::File(2), 2, 0),
         ];
-        let mut formatted = DiskMap { raw: "".to_string(), map: vec![] };
-        formatted.ungroup_blocks_into_map(&files);
-        formatted.sort_files();
-        assert_eq!(formatted.map, vec![BlockType::File(0), BlockType::File(2), BlockType::File(2), BlockType::File(1), BlockType::File(1), BlockType::File(1), BlockType::Free, BlockType::Free]);
+        let mut formatted = DiskMap::from_spans("", files);
+        let moves = formatted.sort_files(Fit::First);
+        assert_eq!(moves, vec![FileMove { file: span(BlockType::File(2), 2, 6), to: 1 }]);
+        assert_eq!(formatted.spans, vec![
+            span(BlockType::File(0), 1, 0),
+            span(BlockType::File(2), 2, 1),
+            span(BlockType::File(1), 3, 3),
+            span(BlockType::Free, 2, 6),
+        ]);
     }
 
     #[test]
-    fn it_will_ungroup_blocks_into_map() {
-        let files = vec![
-            AFile { block_type: BlockType::File(0), blocks: 1, index_of_first_block: 0 },
-            AFile { block_type: BlockType::Free, blocks: 2, index_of_first_block: 1 },
-            AFile { block_type: BlockType::File(1), blocks: 3, index_of_first_block: 3 },
+    fn it_will_render_in_puzzle_notation() {
+        let formatted = format_input(EXAMPLE).unwrap();
+        assert_eq!(formatted.to_string(), "00...111...2...333.44.5555.6666.777.888899");
+
+        let mut formatted = format_input("1010101010101010101011\n").unwrap();
+        assert_eq!(formatted.to_string(), "01234567890.");
+        formatted.sort_blocks();
+        assert_eq!(formatted.render(true), "\x1b[31m0\x1b[0m\x1b[31m1\x1b[0m\x1b[31m2\x1b[0m\x1b[31m3\x1b[0m\
+\x1b[31m4\x1b[0m\x1b[31m5\x1b[0m\x1b[31m6\x1b[0m\x1b[31m7\x1b[0m\x1b[31m8\x1b[0m\x1b[31m9\x1b[0m\x1b[32m0\x1b[0m.");
+    }
+
+    #[test]
+    fn it_will_replay_each_file_move() {
+        let formatted = format_input(EXAMPLE).unwrap();
+        let mut sorted = formatted.clone();
+        let moves = sorted.sort_files(Fit::First);
+        let replay = formatted.replay(&moves, false, None);
+        assert_eq!(&replay[..3], [
+            "start:  00...111...2...333.44.5555.6666.777.888899",
+            "move file 9 (2 blocks) from 40 to 2",
+            "  after:  0099.111...2...333.44.5555.6666.777.8888..",
+        ]);
+        assert_eq!(replay.len(), 1 + 2 * moves.len());
+        assert_eq!(replay.last().unwrap(), &format!("  after:  {}", sorted));
+
+        let replay = formatted.replay(&moves, false, Some(1));
+        assert_eq!(replay.len(), 4);
+        assert_eq!(replay[3], format!("stopped after 1 of {} moves", moves.len()));
+        assert_eq!(sorted.to_string(), "00992111777.44.333....5555.6666.....8888..");
+    }
+
+    #[test]
+    fn it_will_compact_with_each_strategy() {
+        let formatted = format_input("1211131\n").unwrap();
+        for (strategy, expected) in [
+            ("blocks", "0321......"),
+            ("first-fit", "0321......"),
+            ("best-fit", "021.3....."),
+            ("worst-fit", "021...3..."),
+            ("defragment", "0123......"),
+        ] {
+            let mut compacted = formatted.clone();
+            compacted.compact(strategy.parse().unwrap());
+            assert_eq!(compacted.to_string(), expected, "{}", strategy);
+        }
+        assert!("next-fit".parse::<Strategy>().is_err());
+    }
+
+    #[test]
+    fn it_will_replay_any_strategy() {
+        let formatted = format_input(EXAMPLE).unwrap();
+        for strategy in ["blocks", "best-fit", "worst-fit", "defragment"] {
+            let mut compacted = formatted.clone();
+            let moves = compacted.compact(strategy.parse().unwrap());
+            let replay = formatted.replay(&moves, false, None);
+            assert_eq!(replay.last().unwrap(), &format!("  after:  {}", compacted), "{}", strategy);
+        }
+    }
+
+    #[test]
+    fn it_will_count_moves_and_fragmentation() {
+        let formatted = format_input(EXAMPLE).unwrap();
+        let stats = |strategy: Strategy| {
+            let mut compacted = formatted.clone();
+            let moves = compacted.compact(strategy);
+            Stats::of(strategy, &moves, &compacted).to_string()
+        };
+        assert_eq!(stats(Strategy::Files(Fit::First)), "first-fit made 4 moves of 8 blocks, leaving 5 gaps and 0 split files, checksum 2858");
+        assert_eq!(stats(Strategy::Blocks), "blocks made 7 moves of 12 blocks, leaving 0 gaps and 2 split files, checksum 1928");
+        assert_eq!(stats(Strategy::Defragment).split(", checksum").next().unwrap(), "defragment made 9 moves of 26 blocks, leaving 0 gaps and 0 split files");
+    }
+
+    #[test]
+    fn it_will_encode_the_dense_format() {
+        assert_eq!(format_input(EXAMPLE).unwrap().encode(), "2333133121414131402");
+
+        let mut compacted = format_input(EXAMPLE).unwrap();
+        compacted.sort_files(Fit::First);
+        assert_eq!(compacted.encode(), "20201030312134414542");
+        assert_eq!(DiskMap::from_spans("", [span(BlockType::File(0), 12, 0), span(BlockType::Free, 10, 0)]).encode(), "903901");
+    }
+
+    #[test]
+    fn it_will_read_back_what_it_encodes() {
+        let mut seed = 0x2024_u64;
+        let mut digit = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            char::from(b'1' + (seed % 9) as u8)
+        };
+        for length in 1..200 {
+            let raw: String = (0..length).map(|_| digit()).collect();
+            let formatted = DiskMap::new(&raw);
+            assert_eq!(formatted.encode(), raw);
+            for strategy in ["blocks", "first-fit", "best-fit", "worst-fit", "defragment"] {
+                let mut compacted = formatted.clone();
+                compacted.compact(strategy.parse().unwrap());
+                let encoded = compacted.encode();
+                let read_back = DiskMap::new(&encoded);
+                let free = |map: &DiskMap| map.to_string().chars().map(|c| c == '.').collect::<Vec<bool>>();
+                assert_eq!(free(&read_back), free(&compacted), "{} {}", strategy, raw);
+                assert_eq!(read_back.encode(), encoded, "{} {}", strategy, raw);
+            }
+        }
+    }
+
+    #[test]
+    fn it_will_lay_out_spans_end_to_end() {
+        let spans = vec![
+            span(BlockType::File(0), 1, 0),
+            span(BlockType::Free, 2, 0),
+            span(BlockType::Free, 0, 0),
+            span(BlockType::Free, 1, 0),
+            span(BlockType::File(1), 3, 0),
         ];
-        let mut formatted = DiskMap { raw: "".to_string(), map: vec![] };
-        formatted.ungroup_blocks_into_map(&files);
-        assert_eq!(formatted.map, vec![BlockType::File(0), BlockType::Free, BlockType::Free, BlockType::File(1), BlockType::File(1), BlockType::File(1)]);
+        let formatted = DiskMap::from_spans("", spans);
+        assert_eq!(formatted.spans, vec![
+            span(BlockType::File(0), 1, 0),
+            span(BlockType::Free, 3, 1),
+            span(BlockType::File(1), 3, 4),
+        ]);
+        assert_eq!(formatted.len(), 7);
     }
 
-    fn create_file_input() -> File {
-        let mut temp_file = NamedTempFile::new().unwrap();
-        writeln!(temp_file, "2333133121414131402").unwrap();
-        temp_file.reopen().unwrap()
+    #[test]
+    fn it_will_compact_a_large_disk_quickly() {
+        let raw: String = (0..200_001).map(|i| char::from(b'1' + (i * 7 % 9) as u8)).collect();
+        let formatted = DiskMap::new(&raw);
+        let blocks = formatted.len();
+
+        let mut blocks_sorted = formatted.clone();
+        blocks_sorted.sort_blocks();
+        assert_eq!(blocks_sorted.len(), blocks);
+        assert_eq!(blocks_sorted.files().last().map(|file| file.start + file.blocks), Some(blocks_sorted.files().map(|file| file.blocks).sum()));
+
+        let mut files_sorted = formatted.clone();
+        files_sorted.sort_files(Fit::First);
+        assert_eq!(files_sorted.len(), blocks);
+        assert_eq!(files_sorted.files().count(), 100_001);
     }
+
+    const EXAMPLE: &str = "2333133121414131402\n";
 }
\ No newline at end of file
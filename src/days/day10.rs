@@ -1,27 +1,38 @@
 use std::collections::HashSet;
-use std::fs::File;
-use std::io::{self, BufRead, Read};
 use std::vec;
 
+use crate::grid::{Grid, Pos};
+use crate::parse::ParseError;
+use crate::solution::Solution;
+
 const TRAIL_LENGTH: usize = 10;
 
-pub fn solve(input: File) {
-    let input = format_input(input);
-    println!("Part 1: {}", solve_part1(&input));
-    println!("Part 2: {}", solve_part2(&input));
-}
+pub struct Day10;
 
-fn format_input(input: File) -> TrailMap {
-    let reader = io::BufReader::new(input);
-    let mut map = Vec::new();
-    for (i, line) in reader.lines().enumerate() {
-        let mut row = Vec::new();
-        for (j, c) in line.unwrap().chars().enumerate() {
-            row.push(TrailPoint { x: i, y: j, value: c.to_digit(10).unwrap() });
-        }
-        map.push(row);
+impl Solution for Day10 {
+    const DAY: u32 = 10;
+    const TITLE: &'static str = "Hoof It";
+
+    type Input = TrailMap;
+    type Output1 = u32;
+    type Output2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        format_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        solve_part2(input)
     }
-    TrailMap { map }
+}
+
+fn format_input(input: &str) -> Result<TrailMap, ParseError> {
+    let map = Grid::parse(input, |c| c.to_digit(10), "a height from 0 to 9")?;
+    Ok(TrailMap { map })
 }
 
 fn solve_part1(input: &TrailMap) -> u32 {
@@ -40,7 +51,7 @@ fn solve_part2(input: &TrailMap) -> u32 {
     trails.len() as u32
 }
 
-fn get_unique_trail_endpoints_for_trailhead(trails: &Vec<Trail>, trailhead: &TrailPoint) -> Vec<TrailPoint> {
+fn get_unique_trail_endpoints_for_trailhead(trails: &[Trail], trailhead: &TrailPoint) -> Vec<TrailPoint> {
     let mut endpoints = HashSet::new();
     for trail in trails {
         if trail.points.first().unwrap() == trailhead {
@@ -51,8 +62,8 @@ fn get_unique_trail_endpoints_for_trailhead(trails: &Vec<Trail>, trailhead: &Tra
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
-struct TrailMap {
-    map: Vec<Vec<TrailPoint>>,
+pub struct TrailMap {
+    map: Grid<u32>,
 }
 
 impl TrailMap {
@@ -60,22 +71,21 @@ impl TrailMap {
         let trailheads = self.find_trailheads();
         let mut trails = Vec::new();
         for trailhead in trailheads {
-            let mut trail = Trail { points: vec![trailhead.clone()] };
+            let trail = Trail { points: vec![trailhead.clone()] };
             trails.extend(self.walk_trail(trail));
         }
         trails
     }
 
     fn find_trailheads(&self) -> Vec<TrailPoint> {
-        let mut trailheads = Vec::new();
-        for i in 0..self.map.len() {
-            for j in 0..self.map[i].len() {
-                if self.map[i][j].value == 0 {
-                    trailheads.push(self.map[i][j].clone());
-                }
-            }
-        }
-        trailheads
+        self.map.iter()
+            .filter(|(_, &value)| value == 0)
+            .map(|(pos, _)| self.point(pos))
+            .collect()
+    }
+
+    fn point(&self, (x, y): Pos) -> TrailPoint {
+        TrailPoint { x, y, value: self.map[(x, y)] }
     }
 
     fn walk_trail(&self, trail: Trail) -> Vec<Trail> {
@@ -97,20 +107,7 @@ impl TrailMap {
     }
 
     fn get_adjacent_points(&self, point: TrailPoint) -> Vec<TrailPoint> {
-        let mut adjacent_points = Vec::new();
-        if point.x > 0 {
-            adjacent_points.push(self.map[point.x - 1][point.y].clone());
-        }
-        if point.x < self.map.len() - 1 {
-            adjacent_points.push(self.map[point.x + 1][point.y].clone());
-        }
-        if point.y > 0 {
-            adjacent_points.push(self.map[point.x][point.y - 1].clone());
-        }
-        if point.y < self.map[point.x].len() - 1 {
-            adjacent_points.push(self.map[point.x][point.y + 1].clone());
-        }
-        adjacent_points
+        self.map.neighbours4((point.x, point.y)).map(|pos| self.point(pos)).collect()
     }
 }
 
@@ -151,44 +148,43 @@ impl Trail {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use tempfile::NamedTempFile;
-    use std::io::Write;
 
     #[test]
     fn it_will_solve_part1() {
-        let input = create_file_input();
-        let formatted_input = format_input(input);
+        let formatted_input = format_input(EXAMPLE).unwrap();
         assert_eq!(solve_part1(&formatted_input), 36);
     }
 
     #[test]
     fn it_will_solve_part2() {
-        let input = create_file_input();
-        let formatted_input = format_input(input);
+        let formatted_input = format_input(EXAMPLE).unwrap();
         assert_eq!(solve_part2(&formatted_input), 81);
     }
 
     #[test]
     fn it_will_format_input() {
-        let mut temp_file = NamedTempFile::new().unwrap();
-        writeln!(temp_file, "890").unwrap();
-        writeln!(temp_file, "781").unwrap();
-        writeln!(temp_file, "204").unwrap();
-        assert_eq!(format_input(temp_file.reopen().unwrap()), 
+        let input = "890\n781\n204\n";
+        assert_eq!(format_input(input).unwrap(), 
             TrailMap {
-                map: vec![
-                    vec![TrailPoint { x: 0, y: 0, value: 8 }, TrailPoint { x: 0, y: 1, value: 9 }, TrailPoint { x: 0, y: 2, value: 0 }],
-                    vec![TrailPoint { x: 1, y: 0, value: 7 }, TrailPoint { x: 1, y: 1, value: 8 }, TrailPoint { x: 1, y: 2, value: 1 }],
-                    vec![TrailPoint { x: 2, y: 0, value: 2 }, TrailPoint { x: 2, y: 1, value: 0 }, TrailPoint { x: 2, y: 2, value: 4 }],
-                ],
+                map: Grid::from_rows(vec![
+                    vec![8, 9, 0],
+                    vec![7, 8, 1],
+                    vec![2, 0, 4],
+                ]),
             }
         );
     }
 
+    #[test]
+    fn it_will_report_a_character_that_is_not_a_height() {
+        let error = format_input("890\n7.1\n").err().unwrap();
+        assert_eq!((error.line, error.column), (2, 2));
+    }
+
     #[test]
     fn it_will_check_if_trail_is_complete() {
         let trail = Trail { points: vec![TrailPoint { x: 0, y: 0, value: 8 }, TrailPoint { x: 0, y: 1, value: 9 }, TrailPoint { x: 0, y: 2, value: 0 }] };
-        assert_eq!(trail.is_complete(), false);
+        assert!(!trail.is_complete());
         let trail = Trail { points: vec![
             TrailPoint { x: 0, y: 2, value: 0 }, 
             TrailPoint { x: 0, y: 3, value: 1 }, 
@@ -201,43 +197,33 @@ mod tests {
             TrailPoint { x: 0, y: 0, value: 8 }, 
             TrailPoint { x: 0, y: 1, value: 9 }
         ]};
-        assert_eq!(trail.is_complete(), true);
+        assert!(trail.is_complete());
     }
 
     #[test]
     fn it_will_find_trailheads() {
-        let mut temp_file = NamedTempFile::new().unwrap();
-        writeln!(temp_file, "890").unwrap();
-        writeln!(temp_file, "781").unwrap();
-        writeln!(temp_file, "204").unwrap();
-        let file = temp_file.reopen().unwrap();
-        let formatted_input = format_input(file);
+        let input = "890\n781\n204\n";
+        let formatted_input = format_input(input).unwrap();
         assert_eq!(formatted_input.find_trailheads(), vec![TrailPoint { x: 0, y: 2, value: 0 }, TrailPoint { x: 2, y: 1, value: 0 }]);
     }
 
     #[test]
     fn it_will_find_adjacent_points() {
-        let mut temp_file = NamedTempFile::new().unwrap();
-        writeln!(temp_file, "890").unwrap();
-        writeln!(temp_file, "781").unwrap();
-        writeln!(temp_file, "204").unwrap();
-        let file = temp_file.reopen().unwrap();
-        let formatted_input = format_input(file);
+        let input = "890\n781\n204\n";
+        let formatted_input = format_input(input).unwrap();
         let point = TrailPoint { x: 1, y: 1, value: 8 };
         let adjacents = formatted_input.get_adjacent_points(point);
         assert_eq!(adjacents, vec![TrailPoint { x: 0, y: 1, value: 9 }, TrailPoint { x: 2, y: 1, value: 0 }, TrailPoint { x: 1, y: 0, value: 7 }, TrailPoint { x: 1, y: 2, value: 1 }]);
     }
 
-    fn create_file_input() -> File {
-        let mut temp_file = NamedTempFile::new().unwrap();
-        writeln!(temp_file, "89010123").unwrap();
-        writeln!(temp_file, "78121874").unwrap();
-        writeln!(temp_file, "87430965").unwrap();
-        writeln!(temp_file, "96549874").unwrap();
-        writeln!(temp_file, "45678903").unwrap();
-        writeln!(temp_file, "32019012").unwrap();
-        writeln!(temp_file, "01329801").unwrap();
-        writeln!(temp_file, "10456732").unwrap();
-        temp_file.reopen().unwrap()
-    }
+    const EXAMPLE: &str = "\
+89010123
+78121874
+87430965
+96549874
+45678903
+32019012
+01329801
+10456732
+";
 }
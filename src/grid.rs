@@ -0,0 +1,257 @@
+use std::fmt;
+use std::iter;
+use std::ops::{Index, IndexMut};
+
+use crate::parse::{self, ParseError};
+
+/// A cell's position as `(row, column)`, counting from the top left.
+pub type Pos = (usize, usize);
+
+/// A move between cells as `(rows, columns)`.
+pub type Step = (isize, isize);
+
+/// Up, down, left and right.
+pub const ORTHOGONAL: [Step; 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+/// Every direction including diagonals, in reading order.
+pub const ALL_DIRECTIONS: [Step; 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+/// A rectangular grid of cells, stored row by row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// A `width` by `height` grid with every cell set to `fill`.
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid { width, height, cells: vec![fill; width * height] }
+    }
+
+    /// Builds a grid from rows, which must all be the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.len());
+        assert!(rows.iter().all(|row| row.len() == width), "every row of a grid must be {} wide", width);
+        Grid { width, height, cells: rows.into_iter().flatten().collect() }
+    }
+
+    /// Parses one cell per character. `cell` returns `None` for characters that aren't
+    /// allowed, which are reported as expecting `expected`.
+    pub fn parse(input: &str, mut cell: impl FnMut(char) -> Option<T>, expected: &str) -> Result<Self, ParseError> {
+        let mut rows = Vec::new();
+        for line in parse::lines(input) {
+            let row = line.text.chars().enumerate()
+                .map(|(i, c)| cell(c).ok_or_else(|| line.error_at(i + 1, expected)))
+                .collect::<Result<Vec<T>, ParseError>>()?;
+            if let Some(first) = rows.first().map(|first: &Vec<T>| first.len()) {
+                if row.len() != first {
+                    return Err(line.error_at(row.len().min(first) + 1, format!("a row {} characters wide", first)));
+                }
+            }
+            rows.push(row);
+        }
+        Ok(Grid::from_rows(rows))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos).then(|| &self.cells[pos.0 * self.width + pos.1])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        let width = self.width;
+        self.contains(pos).then(|| &mut self.cells[pos.0 * width + pos.1])
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        pos.0 < self.height && pos.1 < self.width
+    }
+
+    /// The position at a signed row and column, if it's inside the grid.
+    pub fn checked(&self, row: isize, column: isize) -> Option<Pos> {
+        let pos = (usize::try_from(row).ok()?, usize::try_from(column).ok()?);
+        self.contains(pos).then_some(pos)
+    }
+
+    /// The position one `step` away from `pos`, if it's inside the grid.
+    pub fn offset(&self, pos: Pos, step: Step) -> Option<Pos> {
+        self.checked(pos.0 as isize + step.0, pos.1 as isize + step.1)
+    }
+
+    /// The up to four cells above, below, left and right of `pos`, in that order.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        ORTHOGONAL.into_iter().filter_map(move |step| self.offset(pos, step))
+    }
+
+    /// The up to eight cells surrounding `pos`, in reading order.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        ALL_DIRECTIONS.into_iter().filter_map(move |step| self.offset(pos, step))
+    }
+
+    /// Positions from `start` repeatedly moving by `step` until leaving the grid.
+    pub fn ray(&self, start: Pos, step: Step) -> impl Iterator<Item = Pos> + '_ {
+        iter::successors(self.contains(start).then_some(start), move |&pos| self.offset(pos, step))
+    }
+
+    /// Every position in reading order.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> + '_ {
+        (0..self.height).flat_map(move |row| (0..self.width).map(move |column| (row, column)))
+    }
+
+    /// Every cell with its position, in reading order.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// The first position, in reading order, whose cell matches.
+    pub fn find(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Pos> {
+        self.iter().find(|(_, cell)| predicate(cell)).map(|(pos, _)| pos)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// Each column from top to bottom, left to right.
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |column| self.cells_along((0, column), (1, 0)))
+    }
+
+    /// Each diagonal running down and right, starting from the bottom left corner.
+    pub fn diagonals_down_right(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let starts = (0..self.height).rev().map(|row| (row, 0)).chain((1..self.width).map(|column| (0, column)));
+        starts.map(move |start| self.cells_along(start, (1, 1)))
+    }
+
+    /// Each diagonal running down and left, starting from the top left corner.
+    pub fn diagonals_down_left(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let last_column = self.width.saturating_sub(1);
+        let starts = (0..self.width).map(|column| (0, column)).chain((1..self.height).map(move |row| (row, last_column)));
+        starts.map(move |start| self.cells_along(start, (1, -1)))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() }
+    }
+
+    fn cells_along(&self, start: Pos, step: Step) -> impl Iterator<Item = &T> {
+        self.ray(start, step).map(move |pos| &self[pos])
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos).unwrap_or_else(|| panic!("{:?} is outside a {}x{} grid", pos, self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(pos).unwrap_or_else(|| panic!("{:?} is outside a {}x{} grid", pos, width, height))
+    }
+}
+
+/// Draws the grid one row per line, the way puzzle inputs are written.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn letters() -> Grid<char> {
+        Grid::parse("abc\ndef\nghi\njkl\n", Some, "a letter").unwrap()
+    }
+
+    fn text<'a>(cells: impl Iterator<Item = &'a char>) -> String {
+        cells.collect()
+    }
+
+    #[test]
+    fn it_will_fill_a_new_grid() {
+        let grid = Grid::new(3, 2, 0);
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.to_string(), "000\n000\n");
+    }
+
+    #[test]
+    fn it_will_parse_a_grid_of_cells() {
+        let grid = Grid::parse("12\n34\n", |c| c.to_digit(10), "a digit").unwrap();
+        assert_eq!((grid.width(), grid.height()), (2, 2));
+        assert_eq!(grid[(1, 0)], 3);
+        assert_eq!(grid.to_string(), "12\n34\n");
+    }
+
+    #[test]
+    fn it_will_report_bad_cells_and_ragged_rows() {
+        let error = Grid::parse("12\n3x\n", |c| c.to_digit(10), "a digit").unwrap_err();
+        assert_eq!((error.line, error.column, error.expected.as_str()), (2, 2, "a digit"));
+
+        let error = Grid::parse("123\n45\n", |c| c.to_digit(10), "a digit").unwrap_err();
+        assert_eq!((error.line, error.column, error.expected.as_str()), (2, 3, "a row 3 characters wide"));
+    }
+
+    #[test]
+    fn it_will_check_bounds() {
+        let grid = letters();
+        assert_eq!(grid.get((3, 2)), Some(&'l'));
+        assert_eq!(grid.get((4, 0)), None);
+        assert_eq!(grid.get((0, 3)), None);
+        assert_eq!(grid.checked(-1, 0), None);
+        assert_eq!(grid.offset((0, 0), (1, 1)), Some((1, 1)));
+        assert_eq!(grid.offset((0, 0), (0, -1)), None);
+    }
+
+    #[test]
+    fn it_will_find_neighbours() {
+        let grid = letters();
+        assert_eq!(grid.neighbours4((0, 0)).collect::<Vec<Pos>>(), vec![(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbours4((1, 1)).collect::<Vec<Pos>>(), vec![(0, 1), (2, 1), (1, 0), (1, 2)]);
+        assert_eq!(grid.neighbours8((0, 0)).collect::<Vec<Pos>>(), vec![(0, 1), (1, 0), (1, 1)]);
+        assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+    }
+
+    #[test]
+    fn it_will_walk_rows_columns_and_diagonals() {
+        let grid = letters();
+        assert_eq!(grid.rows().map(|row| row.iter().collect()).collect::<Vec<String>>(), vec!["abc", "def", "ghi", "jkl"]);
+        assert_eq!(grid.columns().map(text).collect::<Vec<String>>(), vec!["adgj", "behk", "cfil"]);
+        assert_eq!(grid.diagonals_down_right().map(text).collect::<Vec<String>>(), vec!["j", "gk", "dhl", "aei", "bf", "c"]);
+        assert_eq!(grid.diagonals_down_left().map(text).collect::<Vec<String>>(), vec!["a", "bd", "ceg", "fhj", "ik", "l"]);
+        assert_eq!(text(grid.ray((1, 0), (0, 1)).map(|pos| &grid[pos])), "def");
+    }
+
+    #[test]
+    fn it_will_find_and_update_cells() {
+        let mut grid = letters();
+        assert_eq!(grid.find(|&c| c == 'h'), Some((2, 1)));
+        grid[(2, 1)] = '#';
+        assert_eq!(grid.find(|&c| c == 'h'), None);
+        assert_eq!(grid.map(|&c| c == '#').iter().filter(|(_, &wall)| wall).count(), 1);
+    }
+}
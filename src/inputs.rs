@@ -0,0 +1,100 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+pub const INPUT_DIR: &str = "src/inputs";
+
+/// The file for a day's input: `dayNN.txt`, or `dayNN.<variant>.txt` for a named variant
+/// such as `day06.example.txt`.
+pub fn input_file_name(day: u32, variant: Option<&str>) -> String {
+    match variant {
+        Some(variant) => format!("day{:02}.{}.txt", day, variant),
+        None => format!("day{:02}.txt", day),
+    }
+}
+
+pub fn input_path(day: u32, variant: Option<&str>) -> PathBuf {
+    Path::new(INPUT_DIR).join(input_file_name(day, variant))
+}
+
+pub fn resolve_input(day: u32, variant: Option<&str>) -> Result<PathBuf, String> {
+    resolve_input_in(Path::new(INPUT_DIR), day, variant)
+}
+
+/// Reads a whole input file, treating `-` as stdin.
+pub fn read_input(path: &Path) -> io::Result<String> {
+    if path == Path::new("-") {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        Ok(input)
+    } else {
+        fs::read_to_string(path)
+    }
+}
+
+/// Finds a day's input in `dir`, explaining which inputs do exist for that day if it's missing.
+pub fn resolve_input_in(dir: &Path, day: u32, variant: Option<&str>) -> Result<PathBuf, String> {
+    let path = dir.join(input_file_name(day, variant));
+    if path.is_file() {
+        return Ok(path);
+    }
+    let available = available_inputs(dir, day);
+    let hint = if available.is_empty() {
+        format!("There are no inputs for day {} in {}.", day, dir.display())
+    } else {
+        format!("Available inputs for day {}: {}", day, available.join(", "))
+    };
+    Err(format!("Input file {} does not exist. {}", path.display(), hint))
+}
+
+fn available_inputs(dir: &Path, day: u32) -> Vec<String> {
+    let prefix = format!("day{:02}.", day);
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .filter(|name| name.starts_with(&prefix) && name.ends_with(".txt"))
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use tempfile::tempdir;
+
+    #[test]
+    fn it_will_name_input_files_by_day_and_variant() {
+        assert_eq!(input_file_name(6, None), "day06.txt");
+        assert_eq!(input_file_name(6, Some("example")), "day06.example.txt");
+        assert_eq!(input_file_name(10, Some("alt")), "day10.alt.txt");
+    }
+
+    #[test]
+    fn it_will_resolve_an_existing_input() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("day06.txt")).unwrap();
+        File::create(dir.path().join("day06.example.txt")).unwrap();
+        assert_eq!(resolve_input_in(dir.path(), 6, None), Ok(dir.path().join("day06.txt")));
+        assert_eq!(resolve_input_in(dir.path(), 6, Some("example")), Ok(dir.path().join("day06.example.txt")));
+    }
+
+    #[test]
+    fn it_will_list_the_available_inputs_when_one_is_missing() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("day06.txt")).unwrap();
+        File::create(dir.path().join("day06.alt.txt")).unwrap();
+        File::create(dir.path().join("day07.txt")).unwrap();
+        let error = resolve_input_in(dir.path(), 6, Some("example")).unwrap_err();
+        assert!(error.contains("day06.example.txt does not exist"));
+        assert!(error.ends_with("Available inputs for day 6: day06.alt.txt, day06.txt"));
+
+        let error = resolve_input_in(dir.path(), 8, None).unwrap_err();
+        assert!(error.contains("There are no inputs for day 8"));
+    }
+}
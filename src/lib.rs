@@ -0,0 +1,59 @@
+pub mod answer;
+pub mod grid;
+pub mod inputs;
+pub mod logging;
+pub mod options;
+pub mod parallel;
+pub mod parse;
+pub mod search;
+pub mod solution;
+pub mod verify;
+
+use solution::Day;
+
+/// Declares the day modules and registers each one's `Solution` in `DAYS`.
+/// Adding a new day only needs its module and a line here.
+macro_rules! days {
+    ($($module:ident::$solution:ident),* $(,)?) => {
+        pub mod days {
+            $(pub mod $module;)*
+        }
+
+        pub const DAYS: &[Day] = &[$(Day::of::<days::$module::$solution>()),*];
+    };
+}
+
+days!(
+    day01::Day01,
+    day02::Day02,
+    day03::Day03,
+    day04::Day04,
+    day05::Day05,
+    day06::Day06,
+    day07::Day07,
+    day08::Day08,
+    day09::Day09,
+    day10::Day10,
+);
+
+pub fn find_day(number: u32) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_will_register_days_in_order() {
+        let numbers: Vec<u32> = DAYS.iter().map(|day| day.number).collect();
+        assert!(!numbers.is_empty());
+        assert!(numbers.windows(2).all(|pair| pair[0] < pair[1]), "days are out of order or repeated: {:?}", numbers);
+    }
+
+    #[test]
+    fn it_will_find_a_registered_day() {
+        assert_eq!(find_day(7).unwrap().title, "Bridge Repair");
+        assert!(find_day(25).is_none());
+    }
+}
@@ -0,0 +1,78 @@
+use log::{LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+/// Writes `log` records to stderr, optionally only for some days. Days log under their
+/// module path, so a day's messages can be picked out by its `dayNN` target.
+#[derive(Debug)]
+pub struct Logger {
+    level: LevelFilter,
+    days: Vec<u32>,
+}
+
+impl Logger {
+    /// `days` limits day logging to those days; an empty list lets every day through.
+    pub fn new(level: LevelFilter, days: Vec<u32>) -> Self {
+        Logger { level, days }
+    }
+
+    pub fn init(self) -> Result<(), SetLoggerError> {
+        log::set_max_level(self.level);
+        log::set_boxed_logger(Box::new(self))
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+            && (self.days.is_empty() || day_of(metadata.target()).is_none_or(|day| self.days.contains(&day)))
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            let target = record.target().rsplit("::").next().unwrap_or_default();
+            eprintln!("[{:<5} {}] {}", record.level(), target, record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// The day a log target belongs to, for targets inside `days::dayNN`.
+fn day_of(target: &str) -> Option<u32> {
+    target.split("::")
+        .skip_while(|&module| module != "days")
+        .nth(1)
+        .and_then(|module| module.strip_prefix("day"))
+        .and_then(|number| number.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log::Level;
+
+    fn enabled(logger: &Logger, level: Level, target: &str) -> bool {
+        logger.enabled(&Metadata::builder().level(level).target(target).build())
+    }
+
+    #[test]
+    fn it_will_find_the_day_of_a_target() {
+        assert_eq!(day_of("advent_of_code_2024::days::day06"), Some(6));
+        assert_eq!(day_of("advent_of_code_2024::days::day10::inner"), Some(10));
+        assert_eq!(day_of("advent_of_code_2024::solution"), None);
+    }
+
+    #[test]
+    fn it_will_filter_by_level() {
+        let logger = Logger::new(LevelFilter::Debug, Vec::new());
+        assert!(enabled(&logger, Level::Debug, "advent_of_code_2024::days::day09"));
+        assert!(!enabled(&logger, Level::Trace, "advent_of_code_2024::days::day09"));
+    }
+
+    #[test]
+    fn it_will_filter_by_day() {
+        let logger = Logger::new(LevelFilter::Trace, vec![6, 9]);
+        assert!(enabled(&logger, Level::Trace, "advent_of_code_2024::days::day06"));
+        assert!(!enabled(&logger, Level::Trace, "advent_of_code_2024::days::day07"));
+        assert!(enabled(&logger, Level::Debug, "advent_of_code_2024::solution"));
+    }
+}
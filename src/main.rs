@@ -1,25 +1,54 @@
-use std::fs::{self, File};
+use std::env;
 use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 use std::process::exit;
+use std::time::Duration;
 
-mod days {
-    pub mod day01;
-    pub mod day02;
-    pub mod day03;
-    pub mod day04;
-    pub mod day05;
-    pub mod day06;
-    pub mod day07;
-    pub mod day08;
-    pub mod day09;
-    pub mod day10;
-}
+use advent_of_code_2024::answer::Answers;
+use advent_of_code_2024::inputs;
+use advent_of_code_2024::logging::Logger;
+use advent_of_code_2024::options::Options;
+use advent_of_code_2024::parallel;
+use advent_of_code_2024::solution::{Part, RunError};
+use advent_of_code_2024::verify::{ExpectedAnswers, Verdict};
+use advent_of_code_2024::DAYS;
+use cli::Command;
+
+mod cli;
 
 fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (options, args) = cli::parse_global_options(&args).unwrap_or_else(|e| usage_error(e));
+    Logger::new(options.log_level, options.log_days).init().expect("the logger should only be set once");
+    if let Some(threads) = options.threads {
+        parallel::set_threads(threads);
+    }
+    match cli::parse_args(&args) {
+        Ok(Command::Interactive) => run_interactive(),
+        Ok(Command::Help) => println!("{}", cli::USAGE),
+        Ok(Command::Run(run)) => solve_day(run.day, run.input, run.variant.as_deref(), run.part, &run.options),
+        Ok(Command::Verify(verify)) => verify_all(&verify.answers),
+        Ok(Command::All) => run_all(),
+        Err(e) => usage_error(e),
+    }
+}
+
+fn usage_error(e: String) -> ! {
+    eprintln!("{}\n\n{}", e, cli::USAGE);
+    exit(2)
+}
+
+fn run_interactive() {
     startup_prompt();
     let day = day_prompt();
-    let input = select_input_file();
-    solve_day(day, input);
+    solve_day(day, None, None, Part::Both, &Options::default());
+}
+
+fn read_input(path: &Path) -> String {
+    inputs::read_input(path).unwrap_or_else(|e| {
+        eprintln!("Error reading {}: {}.", path.display(), e);
+        exit(1)
+    })
 }
 
 fn startup_prompt() {
@@ -49,52 +78,133 @@ fn day_prompt() -> u32 {
     }
 }
 
-fn select_input_file() -> File {
-    let input_dir = "src/inputs";
-    
-    let entries = fs::read_dir(input_dir).unwrap_or_else(|e| {
-        println!("Error reading directory: {}.", e);
+fn solve_day(day: u32, input: Option<PathBuf>, variant: Option<&str>, part: Part, options: &Options) {
+    let Some(solution) = advent_of_code_2024::find_day(day) else {
+        println!("Day {} not implemented yet", day);
         exit(1)
+    };
+    let path = input.unwrap_or_else(|| {
+        inputs::resolve_input(day, variant).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            exit(1)
+        })
     });
-    let mut files: Vec<_> = entries
-        .filter_map(|entry| entry.ok())
-        .filter(|entry| entry.path().is_file())
-        .collect();
-    
-    files.sort_by(|a, b| a.path().cmp(&b.path()));
-    
-    println!("\nAvailable input files:");
-    for (i, file) in files.iter().enumerate() {
-        println!("{}. {}", i + 1, file.path().display());
+    match solution.run_with(&read_input(&path), part, options) {
+        Ok(answers) => print_answers(&answers),
+        Err(e @ RunError::Option(_)) => usage_error(e.diagnostic()),
+        Err(e) => {
+            eprintln!("{}", e.diagnostic());
+            exit(1)
+        }
     }
-    
-    print!("Select a file (1-{}): ", files.len());
-    io::stdout().flush().unwrap();
-    let mut input = String::new();
-    io::stdin().read_line(&mut input).unwrap_or_else(|e| {
-        println!("Error reading input: {}. Defaulting to first file", e);
-        1
-    });
-    let selection = input.trim().parse::<usize>().unwrap_or(1) - 1;
-    
-    File::open(files[selection].path()).unwrap_or_else(|e| {
-        println!("Error opening file: {}.", e);
+}
+
+fn print_answers(answers: &Answers) {
+    for line in &answers.report {
+        println!("{}", line);
+    }
+    for part in 1..=2 {
+        if let Some(answer) = answers.get(part) {
+            println!("The answer for part {} is: {}", part, answer);
+        }
+    }
+}
+
+fn verify_all(answers_path: &Path) {
+    let expected = ExpectedAnswers::load(answers_path).unwrap_or_else(|e| {
+        eprintln!("{}", e);
         exit(1)
-    })
+    });
+    let mut failed = false;
+    for day in DAYS {
+        let path = inputs::input_path(day.number, None);
+        let input = match inputs::read_input(&path) {
+            Ok(input) => input,
+            Err(e) => {
+                println!("Day {:02}: FAIL  could not read {}: {}", day.number, path.display(), e);
+                failed = true;
+                continue;
+            }
+        };
+        // Each part runs on its own, so a part that fails doesn't hide the other's verdict.
+        for (part, only) in [(1, Part::One), (2, Part::Two)] {
+            let answers = match day.run(&input, only) {
+                Ok(answers) => answers,
+                Err(e @ RunError::Solve { .. }) => {
+                    println!("Day {:02} part {}: FAIL  {}", day.number, part, e);
+                    failed = true;
+                    continue;
+                }
+                Err(e) => {
+                    println!("Day {:02}: FAIL  could not run {}", day.number, path.display());
+                    eprintln!("{}", e.diagnostic());
+                    failed = true;
+                    break;
+                }
+            };
+            let Some(answer) = answers.get(part) else { continue };
+            let verdict = expected.check(day.number, part, answer);
+            match &verdict {
+                Verdict::Fail { expected } => {
+                    println!("Day {:02} part {}: {}  expected {}, got {}", day.number, part, verdict, expected, answer);
+                    failed = true;
+                }
+                _ => println!("Day {:02} part {}: {}  {}", day.number, part, verdict, answer),
+            }
+        }
+    }
+    if failed {
+        exit(1);
+    }
 }
 
-fn solve_day(day: u32, input: File) {
-    match day {
-        1 => days::day01::solve(input),
-        2 => days::day02::solve(input),
-        3 => days::day03::solve(input),
-        4 => days::day04::solve(input),
-        5 => days::day05::solve(input),
-        6 => days::day06::solve(input),
-        7 => days::day07::solve(input),
-        8 => days::day08::solve(input),
-        9 => days::day09::solve(input),
-        10 => days::day10::solve(input),
-        _ => println!("Day {} not implemented yet", day),
-    };
+fn run_all() {
+    println!(
+        "{:<4} {:<22} {:>10} {:>10} {:>10} {:>10}  {:<16} Answer 2",
+        "Day", "Title", "Parse", "Part 1", "Part 2", "Total", "Answer 1"
+    );
+    let mut total = Duration::ZERO;
+    for day in DAYS {
+        let path = inputs::input_path(day.number, None);
+        let Ok(input) = inputs::read_input(&path) else {
+            println!("{:<4} {:<22} missing input {}", day.number, day.title, path.display());
+            continue;
+        };
+        let (answers, timings) = match day.run_timed(&input, Part::Both) {
+            Ok(run) => run,
+            Err(e) => {
+                println!("{:<4} {:<22} failed on {}", day.number, day.title, path.display());
+                eprintln!("{}", e.diagnostic());
+                continue;
+            }
+        };
+        total += timings.total();
+        println!(
+            "{:<4} {:<22} {:>10} {:>10} {:>10} {:>10}  {:<16} {}",
+            day.number,
+            day.title,
+            format_duration(timings.parse),
+            format_optional_duration(timings.part1),
+            format_optional_duration(timings.part2),
+            format_duration(timings.total()),
+            answers.part1.map(|answer| answer.to_string()).unwrap_or_default(),
+            answers.part2.map(|answer| answer.to_string()).unwrap_or_default(),
+        );
+    }
+    println!("{:<27} {:>43}", "Total", format_duration(total));
+}
+
+fn format_optional_duration(duration: Option<Duration>) -> String {
+    duration.map(format_duration).unwrap_or_else(|| String::from("-"))
+}
+
+fn format_duration(duration: Duration) -> String {
+    let micros = duration.as_secs_f64() * 1_000_000.0;
+    if micros < 1_000.0 {
+        format!("{:.1}µs", micros)
+    } else if micros < 1_000_000.0 {
+        format!("{:.2}ms", micros / 1_000.0)
+    } else {
+        format!("{:.2}s", micros / 1_000_000.0)
+    }
 }
@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Day-specific settings passed on the command line as `--option key=value`, or just
+/// `--option key` for a switch.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Options {
+    values: BTreeMap<String, Option<String>>,
+}
+
+impl Options {
+    /// Adds an option written as `key=value` or `key`.
+    pub fn insert(&mut self, option: &str) {
+        match option.split_once('=') {
+            Some((key, value)) => self.values.insert(key.to_string(), Some(value.to_string())),
+            None => self.values.insert(option.to_string(), None),
+        };
+    }
+
+    /// Whether `key` was given at all, with or without a value.
+    pub fn is_set(&self, key: &str) -> bool {
+        self.values.contains_key(key)
+    }
+
+    /// The value given for `key`. A switch given without a value is an error.
+    pub fn get(&self, key: &str) -> Result<Option<&str>, OptionError> {
+        match self.values.get(key) {
+            None => Ok(None),
+            Some(Some(value)) => Ok(Some(value)),
+            Some(None) => Err(OptionError(format!("Option '{}' expects a value, like {}=...", key, key))),
+        }
+    }
+
+    /// Whether `key` was given, and its value if it had one, for options that work either way.
+    pub fn flag(&self, key: &str) -> Option<Option<&str>> {
+        self.values.get(key).map(|value| value.as_deref())
+    }
+
+    /// Where an option that produces output wants it: `key` alone prints it, while
+    /// `key=path` writes it to a file.
+    pub fn destination(&self, key: &str) -> Option<Destination> {
+        self.flag(key).map(|path| match path {
+            Some(path) => Destination::File(PathBuf::from(path)),
+            None => Destination::Terminal,
+        })
+    }
+
+    pub fn parse<T: FromStr>(&self, key: &str) -> Result<Option<T>, OptionError> {
+        let Some(value) = self.get(key)? else { return Ok(None) };
+        value.parse()
+            .map(Some)
+            .map_err(|_| OptionError(format!("Invalid value '{}' for option '{}'", value, key)))
+    }
+
+    /// Fails on the first option that isn't in `known`, so typos don't pass silently.
+    pub fn allow_only(&self, known: &[&str]) -> Result<(), OptionError> {
+        match self.values.keys().find(|key| !known.contains(&key.as_str())) {
+            None => Ok(()),
+            Some(key) if known.is_empty() => Err(OptionError(format!("Unknown option '{}', this day has no options", key))),
+            Some(key) => Err(OptionError(format!("Unknown option '{}', expected one of: {}", key, known.join(", ")))),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Destination {
+    Terminal,
+    File(PathBuf),
+}
+
+/// What a day wants shown alongside its answers, and any files it wants written. The
+/// runner writes the files, so a failed write fails the run.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Report {
+    pub lines: Vec<String>,
+    pub files: Vec<ReportFile>,
+}
+
+/// Output bound for a file, with a description of it like `the rule graph`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReportFile {
+    pub what: String,
+    pub path: PathBuf,
+    pub lines: Vec<String>,
+}
+
+impl Report {
+    /// Adds `lines` to the report, or queues them to be written to a file if that's where
+    /// `destination` says they go.
+    pub fn send(&mut self, destination: &Destination, lines: Vec<String>, what: &str) {
+        match destination {
+            Destination::Terminal => self.lines.extend(lines),
+            Destination::File(path) => self.files.push(ReportFile { what: what.to_string(), path: path.clone(), lines }),
+        }
+    }
+
+    pub fn append(&mut self, other: Report) {
+        self.lines.extend(other.lines);
+        self.files.extend(other.files);
+    }
+}
+
+impl From<Vec<String>> for Report {
+    fn from(lines: Vec<String>) -> Self {
+        Report { lines, files: Vec::new() }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OptionError(pub String);
+
+impl fmt::Display for OptionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for OptionError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(list: &[&str]) -> Options {
+        let mut options = Options::default();
+        for option in list {
+            options.insert(option);
+        }
+        options
+    }
+
+    #[test]
+    fn it_will_read_values_and_switches() {
+        let options = options(&["operators=+,*", "explain", "limit=3"]);
+        assert_eq!(options.get("operators"), Ok(Some("+,*")));
+        assert!(options.is_set("explain"));
+        assert!(options.get("explain").is_err());
+        assert_eq!(options.flag("explain"), Some(None));
+        assert_eq!(options.flag("limit"), Some(Some("3")));
+        assert_eq!(options.flag("missing"), None);
+        assert_eq!(options.parse::<u32>("limit"), Ok(Some(3)));
+        assert_eq!(options.parse::<u32>("missing"), Ok(None));
+        assert!(options.parse::<u32>("operators").is_err());
+    }
+
+    #[test]
+    fn it_will_send_output_where_it_was_asked_for() {
+        let options = options(&["dot", "replay=replay.log"]);
+        assert_eq!(options.destination("dot"), Some(Destination::Terminal));
+        assert_eq!(options.destination("replay"), Some(Destination::File(PathBuf::from("replay.log"))));
+
+        let mut report = Report::from(vec![String::from("a")]);
+        report.send(&Destination::Terminal, vec![String::from("b")], "a line");
+        report.send(&Destination::File(PathBuf::from("out.txt")), vec![String::from("c")], "another line");
+        assert_eq!(report.lines, vec!["a", "b"]);
+        assert_eq!(report.files, vec![ReportFile {
+            what: String::from("another line"),
+            path: PathBuf::from("out.txt"),
+            lines: vec![String::from("c")],
+        }]);
+    }
+
+    #[test]
+    fn it_will_reject_unknown_options() {
+        let options = options(&["operators=+", "explian"]);
+        assert_eq!(options.allow_only(&["operators", "explain"]), Err(OptionError(String::from(
+            "Unknown option 'explian', expected one of: operators, explain"
+        ))));
+        assert!(options.allow_only(&[]).is_err());
+        assert!(Options::default().allow_only(&[]).is_ok());
+    }
+}
@@ -0,0 +1,72 @@
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// The number of threads to use, or 0 for one per available core.
+static THREADS: AtomicUsize = AtomicUsize::new(0);
+
+/// Sets how many threads `map` and `filter` use. 0 means one per available core.
+pub fn set_threads(threads: usize) {
+    THREADS.store(threads, Ordering::Relaxed);
+}
+
+pub fn threads() -> usize {
+    match THREADS.load(Ordering::Relaxed) {
+        0 => thread::available_parallelism().map_or(1, |cores| cores.get()),
+        threads => threads,
+    }
+}
+
+/// Applies `f` to every item across `threads()` threads. Results come back in the same
+/// order as `items`, so the output matches a sequential map exactly.
+pub fn map<T: Sync, U: Send>(items: &[T], f: impl Fn(&T) -> U + Sync) -> Vec<U> {
+    map_with(threads(), items, f)
+}
+
+/// Keeps the items matching `predicate`, checking them in parallel but preserving their order.
+pub fn filter<T: Clone + Sync>(items: &[T], predicate: impl Fn(&T) -> bool + Sync) -> Vec<T> {
+    let keep = map(items, predicate);
+    items.iter().zip(keep).filter(|(_, keep)| *keep).map(|(item, _)| item.clone()).collect()
+}
+
+/// `map` with an explicit thread count. Items are split into one contiguous chunk per thread.
+pub fn map_with<T: Sync, U: Send>(threads: usize, items: &[T], f: impl Fn(&T) -> U + Sync) -> Vec<U> {
+    if threads <= 1 || items.len() <= 1 {
+        return items.iter().map(f).collect();
+    }
+    let chunk_size = items.len().div_ceil(threads);
+    let f = &f;
+    thread::scope(|scope| {
+        let workers: Vec<_> = items.chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || chunk.iter().map(f).collect::<Vec<U>>()))
+            .collect();
+        workers.into_iter()
+            .flat_map(|worker| worker.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_will_match_a_sequential_map_for_any_thread_count() {
+        let items: Vec<u64> = (0..1000).collect();
+        let expected: Vec<u64> = items.iter().map(|n| n * n).collect();
+        for threads in [0, 1, 2, 3, 7, 64, 2000] {
+            assert_eq!(map_with(threads, &items, |n| n * n), expected);
+        }
+    }
+
+    #[test]
+    fn it_will_handle_empty_input() {
+        assert_eq!(map_with(4, &[] as &[u32], |n| n + 1), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn it_will_filter_in_order() {
+        let items: Vec<u32> = (0..100).collect();
+        assert_eq!(filter(&items, |n| n % 7 == 0), vec![0, 7, 14, 21, 28, 35, 42, 49, 56, 63, 70, 77, 84, 91, 98]);
+    }
+}
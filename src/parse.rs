@@ -0,0 +1,135 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Why a day's input could not be parsed, and where. Lines and columns count from 1.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u32,
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    /// The text of the offending line, so the error can point into it.
+    pub text: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, expected: impl Into<String>, text: &str) -> Self {
+        ParseError { day: 0, line, column, expected: expected.into(), text: text.to_string() }
+    }
+
+    /// Errors are raised without knowing which day is parsing; the runner fills it in.
+    pub fn in_day(self, day: u32) -> Self {
+        ParseError { day, ..self }
+    }
+
+    /// Renders the error like a compiler diagnostic, with a caret under the offending column:
+    ///
+    /// ```text
+    /// error: expected a number
+    ///  --> day 01, line 3, column 5
+    ///   |
+    /// 3 | 12  x4
+    ///   |     ^
+    /// ```
+    pub fn diagnostic(&self) -> String {
+        let gutter = " ".repeat(self.line.to_string().len());
+        format!(
+            "error: expected {}\n{}--> day {:02}, line {}, column {}\n{} |\n{} | {}\n{} | {}^",
+            self.expected,
+            gutter, self.day, self.line, self.column,
+            gutter,
+            self.line, self.text,
+            gutter, " ".repeat(self.column.saturating_sub(1)),
+        )
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "day {}, line {}, column {}: expected {}", self.day, self.line, self.column, self.expected)
+    }
+}
+
+impl Error for ParseError {}
+
+/// A line of puzzle input with its line number, so parsers can say where they failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+}
+
+pub fn lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+    input.lines().enumerate().map(|(i, text)| Line { number: i + 1, text })
+}
+
+impl<'a> Line<'a> {
+    pub fn error_at(&self, column: usize, expected: impl Into<String>) -> ParseError {
+        ParseError::new(self.number, column, expected, self.text)
+    }
+
+    /// An error pointing at `token`, which must be a slice of this line.
+    pub fn error(&self, token: &str, expected: impl Into<String>) -> ParseError {
+        self.error_at(self.column_of(token), expected)
+    }
+
+    /// An error pointing just past the end of the line, for something that is missing.
+    pub fn error_at_end(&self, expected: impl Into<String>) -> ParseError {
+        self.error_at(self.text.chars().count() + 1, expected)
+    }
+
+    /// The column `token` starts at, where `token` is a slice of this line.
+    pub fn column_of(&self, token: &str) -> usize {
+        let offset = (token.as_ptr() as usize).wrapping_sub(self.text.as_ptr() as usize);
+        assert!(offset <= self.text.len(), "token is not part of line {}", self.number);
+        self.text[..offset].chars().count() + 1
+    }
+
+    /// Parses `token`, a slice of this line, reporting its position if it isn't valid.
+    pub fn parse<T: FromStr>(&self, token: &str, expected: &str) -> Result<T, ParseError> {
+        token.parse().map_err(|_| self.error(token, expected))
+    }
+
+    pub fn split_once(&self, delimiter: &str, expected: &str) -> Result<(&'a str, &'a str), ParseError> {
+        self.text.split_once(delimiter).ok_or_else(|| self.error_at_end(expected))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_will_number_lines_from_one() {
+        let numbers: Vec<usize> = lines("a\nb\nc\n").map(|line| line.number).collect();
+        assert_eq!(numbers, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn it_will_point_at_the_token_that_failed_to_parse() {
+        let line = lines("1\n12   x4\n").nth(1).unwrap();
+        let token = line.text.split_whitespace().nth(1).unwrap();
+        let error = line.parse::<i32>(token, "a number").unwrap_err().in_day(1);
+        assert_eq!(error, ParseError::new(2, 6, "a number", "12   x4").in_day(1));
+        assert_eq!(error.to_string(), "day 1, line 2, column 6: expected a number");
+    }
+
+    #[test]
+    fn it_will_point_past_the_end_for_missing_text() {
+        let line = lines("12").next().unwrap();
+        let error = line.split_once("   ", "a second number").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 3));
+    }
+
+    #[test]
+    fn it_will_render_a_diagnostic() {
+        let error = ParseError::new(3, 5, "a number", "12  x4").in_day(1);
+        assert_eq!(error.diagnostic(), "\
+error: expected a number
+ --> day 01, line 3, column 5
+  |
+3 | 12  x4
+  |     ^");
+    }
+}
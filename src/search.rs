@@ -0,0 +1,124 @@
+use crate::grid::{Grid, Pos, Step, ALL_DIRECTIONS};
+use crate::parse::{self, ParseError};
+
+/// Where a word was found: the position of its first letter and the direction it reads in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WordMatch {
+    pub start: Pos,
+    pub step: Step,
+}
+
+/// Every place `word` can be read in a straight line, in any of the eight directions,
+/// in reading order of the first letter. Palindromes are found once per direction.
+pub fn find_word(grid: &Grid<char>, word: &str) -> Vec<WordMatch> {
+    if word.is_empty() {
+        return Vec::new();
+    }
+    let length = word.chars().count();
+    grid.positions()
+        .flat_map(|start| ALL_DIRECTIONS.into_iter().map(move |step| WordMatch { start, step }))
+        .filter(|found| grid.ray(found.start, found.step).take(length).map(|pos| grid[pos]).eq(word.chars()))
+        .collect()
+}
+
+/// A rectangular shape of letters to look for, where `.` matches any letter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Template {
+    cells: Grid<Option<char>>,
+}
+
+impl Template {
+    /// Reads a template written one row per line, like a puzzle input. It needs at least
+    /// one letter, since a template of only wildcards would match everywhere.
+    pub fn parse(pattern: &str) -> Result<Self, ParseError> {
+        let cells = Grid::parse(pattern, |c| Some((c != '.').then_some(c)), "a letter or '.'")?;
+        if cells.iter().all(|(_, cell)| cell.is_none()) {
+            let last = parse::lines(pattern).last().unwrap_or(parse::Line { number: 1, text: "" });
+            return Err(last.error_at_end("at least one letter in the template"));
+        }
+        Ok(Template { cells })
+    }
+
+    /// The template turned a quarter turn clockwise.
+    pub fn rotate(&self) -> Self {
+        let (width, height) = (self.cells.width(), self.cells.height());
+        let rows = (0..width)
+            .map(|row| (0..height).map(|column| self.cells[(height - 1 - column, row)]).collect())
+            .collect();
+        Template { cells: Grid::from_rows(rows) }
+    }
+
+    /// The template in each of its four quarter turns, leaving out turns that look the same.
+    pub fn rotations(&self) -> Vec<Template> {
+        let mut rotations: Vec<Template> = Vec::new();
+        let mut turned = self.clone();
+        for _ in 0..4 {
+            let next = turned.rotate();
+            if !rotations.contains(&turned) {
+                rotations.push(turned);
+            }
+            turned = next;
+        }
+        rotations
+    }
+
+    /// The top left corner of every place the template fits, in reading order.
+    pub fn find(&self, grid: &Grid<char>) -> Vec<Pos> {
+        grid.positions().filter(|&corner| self.matches_at(grid, corner)).collect()
+    }
+
+    fn matches_at(&self, grid: &Grid<char>, corner: Pos) -> bool {
+        self.cells.iter().all(|((row, column), cell)| {
+            let letter = grid.get((corner.0 + row, corner.1 + column));
+            letter.is_some_and(|letter| cell.is_none_or(|wanted| wanted == *letter))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn letters(text: &str) -> Grid<char> {
+        Grid::parse(text, Some, "a letter").unwrap()
+    }
+
+    #[test]
+    fn it_will_find_words_in_every_direction() {
+        let grid = letters("CAT\nAAA\nTAC\n");
+        assert_eq!(find_word(&grid, "CAT"), vec![
+            WordMatch { start: (0, 0), step: (0, 1) },
+            WordMatch { start: (0, 0), step: (1, 0) },
+            WordMatch { start: (2, 2), step: (-1, 0) },
+            WordMatch { start: (2, 2), step: (0, -1) },
+        ]);
+        assert_eq!(find_word(&grid, "AAA").len(), 4);
+        assert!(find_word(&grid, "").is_empty());
+        assert!(find_word(&grid, "CATS").is_empty());
+    }
+
+    #[test]
+    fn it_will_find_templates_with_wildcards() {
+        let template = Template::parse("A.\n.B\n").unwrap();
+        let grid = letters("AxAx\nxBxB\n");
+        assert_eq!(template.find(&grid), vec![(0, 0), (0, 2)]);
+        assert_eq!(template.rotate(), Template::parse(".A\nB.\n").unwrap());
+        assert!(Template::parse("ab\nc\n").is_err());
+    }
+
+    #[test]
+    fn it_will_reject_templates_that_would_match_everywhere() {
+        assert!(Template::parse("").is_err());
+        let error = Template::parse("..\n..\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+    }
+
+    #[test]
+    fn it_will_only_keep_distinct_rotations() {
+        assert_eq!(Template::parse("AB\n").unwrap().rotations().len(), 4);
+        assert_eq!(Template::parse("A.\n.A\n").unwrap().rotations().len(), 2);
+        assert_eq!(Template::parse("A\n").unwrap().rotations().len(), 1);
+        let tall = Template::parse("AB\n").unwrap().rotate();
+        assert_eq!((tall.cells.width(), tall.cells.height()), (1, 2));
+    }
+}
@@ -0,0 +1,268 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::answer::{Answers, IntoAnswer, SolveError};
+use crate::options::{OptionError, Options, Report};
+use crate::parse::ParseError;
+
+/// A single day's puzzle: how to parse its input and how to solve each part.
+pub trait Solution {
+    const DAY: u32;
+    const TITLE: &'static str;
+
+    type Input;
+    type Output1: IntoAnswer;
+    type Output2: IntoAnswer;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> Self::Output1;
+    fn part2(input: &Self::Input) -> Self::Output2;
+
+    /// Applies the `--option`s given for this run to the parsed input. Days without any
+    /// options keep this default, which rejects them all.
+    fn configure(_input: &mut Self::Input, options: &Options) -> Result<(), OptionError> {
+        options.allow_only(&[])
+    }
+
+    /// Lines to show alongside a part's answer, usually only when an option asks for them.
+    fn report(_input: &Self::Input, _part: u32) -> Report {
+        Report::default()
+    }
+
+    /// Lines about the input itself rather than either part, shown once whichever parts run.
+    fn describe(_input: &Self::Input) -> Report {
+        Report::default()
+    }
+
+    /// Parses input from any reader, such as stdin or an open file. Malformed input is
+    /// reported as `InvalidData` wrapping the `ParseError`.
+    fn parse_reader(mut reader: impl Read) -> io::Result<Self::Input> {
+        let mut input = String::new();
+        reader.read_to_string(&mut input)?;
+        Self::parse(&input).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.in_day(Self::DAY)))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+    Both,
+}
+
+impl Part {
+    pub fn includes(&self, part: u32) -> bool {
+        match self {
+            Part::One => part == 1,
+            Part::Two => part == 2,
+            Part::Both => true,
+        }
+    }
+}
+
+/// Why a day could not run: its input or its options were invalid, a part found no answer,
+/// or output it was asked to save couldn't be written.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RunError {
+    Parse(ParseError),
+    Option(OptionError),
+    Solve { part: u32, error: SolveError },
+    Write { what: String, path: PathBuf, error: String },
+}
+
+impl RunError {
+    /// Renders the error for the terminal, using the full diagnostic for parse errors.
+    pub fn diagnostic(&self) -> String {
+        match self {
+            RunError::Parse(e) => e.diagnostic(),
+            RunError::Option(_) | RunError::Solve { .. } | RunError::Write { .. } => format!("error: {}", self),
+        }
+    }
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RunError::Parse(e) => write!(f, "{}", e),
+            RunError::Option(e) => write!(f, "{}", e),
+            RunError::Solve { part, error } => write!(f, "part {} could not be solved: {}", part, error),
+            RunError::Write { what, path, error } => write!(f, "could not write {} to {}: {}", what, path.display(), error),
+        }
+    }
+}
+
+impl Error for RunError {}
+
+impl From<ParseError> for RunError {
+    fn from(e: ParseError) -> Self {
+        RunError::Parse(e)
+    }
+}
+
+impl From<OptionError> for RunError {
+    fn from(e: OptionError) -> Self {
+        RunError::Option(e)
+    }
+}
+
+/// The answers from running a day along with how long each phase took.
+type TimedRun = Result<(Answers, Timings), RunError>;
+
+/// A registered day with its `Solution` erased, so every day can live in one table.
+pub struct Day {
+    pub number: u32,
+    pub title: &'static str,
+    run: fn(&str, Part, &Options) -> TimedRun,
+}
+
+impl Day {
+    pub const fn of<S: Solution>() -> Day {
+        Day { number: S::DAY, title: S::TITLE, run: run::<S> }
+    }
+
+    pub fn run(&self, input: &str, part: Part) -> Result<Answers, RunError> {
+        self.run_with(input, part, &Options::default())
+    }
+
+    /// Runs the day with its `--option`s applied.
+    pub fn run_with(&self, input: &str, part: Part, options: &Options) -> Result<Answers, RunError> {
+        (self.run)(input, part, options).map(|(answers, _)| answers)
+    }
+
+    pub fn run_timed(&self, input: &str, part: Part) -> TimedRun {
+        (self.run)(input, part, &Options::default())
+    }
+}
+
+/// How long each phase of a run took. Parts that were not run are `None`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Timings {
+    pub parse: Duration,
+    pub part1: Option<Duration>,
+    pub part2: Option<Duration>,
+}
+
+impl Timings {
+    pub fn total(&self) -> Duration {
+        self.parse + self.part1.unwrap_or_default() + self.part2.unwrap_or_default()
+    }
+}
+
+fn run<S: Solution>(input: &str, part: Part, options: &Options) -> TimedRun {
+    let (parsed, parse) = timed(|| S::parse(input));
+    let mut parsed = parsed.map_err(|e| e.in_day(S::DAY))?;
+    S::configure(&mut parsed, options)?;
+    let (part1, part1_time) = part.includes(1).then(|| timed(|| S::part1(&parsed).into_answer())).unzip();
+    let (part2, part2_time) = part.includes(2).then(|| timed(|| S::part2(&parsed).into_answer())).unzip();
+    let part1 = part1.transpose().map_err(|error| RunError::Solve { part: 1, error })?;
+    let part2 = part2.transpose().map_err(|error| RunError::Solve { part: 2, error })?;
+    let mut report = S::describe(&parsed);
+    for n in (1..=2).filter(|&n| part.includes(n)) {
+        report.append(S::report(&parsed, n));
+    }
+    let report = write_report(report)?;
+    Ok((
+        Answers { part1, part2, report },
+        Timings { parse, part1: part1_time, part2: part2_time },
+    ))
+}
+
+/// Writes the files a report asks for, leaving a note in their place among its lines.
+fn write_report(report: Report) -> Result<Vec<String>, RunError> {
+    let mut lines = report.lines;
+    for file in report.files {
+        fs::write(&file.path, file.lines.join("\n") + "\n").map_err(|e| RunError::Write {
+            what: file.what.clone(),
+            path: file.path.clone(),
+            error: e.to_string(),
+        })?;
+        lines.push(format!("Wrote {} to {}", file.what, file.path.display()));
+    }
+    Ok(lines)
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answer::Answer;
+    use crate::days::day01::Day01;
+    use crate::days::day09::Day09;
+
+    #[test]
+    fn it_will_return_answers_for_the_requested_parts() {
+        let day = Day::of::<Day01>();
+
+        let answers = day.run("3   4\n4   3\n", Part::Both).unwrap();
+        assert_eq!(answers, Answers { part1: Some(Answer::I32(0)), part2: Some(Answer::I32(7)), report: Vec::new() });
+
+        let answers = day.run("3   4\n4   3\n", Part::Two).unwrap();
+        assert_eq!(answers, Answers { part1: None, part2: Some(Answer::I32(7)), report: Vec::new() });
+    }
+
+    #[test]
+    fn it_will_parse_from_a_reader() {
+        let input = Day01::parse_reader("3   4\n4   3\n".as_bytes()).unwrap();
+        assert_eq!(Day01::part2(&input), 7);
+    }
+
+    #[test]
+    fn it_will_report_which_day_failed_to_parse() {
+        let Err(RunError::Parse(error)) = Day::of::<Day01>().run("3   4\n4\n", Part::Both) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((error.day, error.line, error.column), (1, 2, 2));
+
+        let error = Day01::parse_reader("x   4\n".as_bytes()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn it_will_reject_options_a_day_does_not_take() {
+        let mut options = Options::default();
+        options.insert("explain");
+        let error = Day::of::<Day01>().run_with("3   4\n", Part::Both, &options).unwrap_err();
+        assert_eq!(error.diagnostic(), "error: Unknown option 'explain', this day has no options");
+    }
+
+    #[test]
+    fn it_will_write_the_files_a_report_asks_for() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("replay.log");
+        let mut options = Options::default();
+        options.insert(&format!("replay={}", path.display()));
+        let answers = Day::of::<Day09>().run_with("12345\n", Part::Two, &options).unwrap();
+        assert_eq!(answers.report, vec![format!("Wrote the replay of 0 file moves to {}", path.display())]);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "start:  0..111....22222\n");
+
+        let mut options = Options::default();
+        options.insert(&format!("replay={}", dir.path().join("missing").join("replay.log").display()));
+        let error = Day::of::<Day09>().run_with("12345\n", Part::Two, &options).unwrap_err();
+        assert!(matches!(error, RunError::Write { .. }), "{:?}", error);
+    }
+
+    #[test]
+    fn it_will_time_only_the_parts_that_ran() {
+        let (_, timings) = Day::of::<Day01>().run_timed("3   4\n", Part::One).unwrap();
+        assert!(timings.part1.is_some());
+        assert!(timings.part2.is_none());
+        assert_eq!(timings.total(), timings.parse + timings.part1.unwrap());
+    }
+
+    #[test]
+    fn it_will_check_which_parts_to_run() {
+        assert!(Part::One.includes(1));
+        assert!(!Part::One.includes(2));
+        assert!(!Part::Two.includes(1));
+        assert!(Part::Both.includes(1) && Part::Both.includes(2));
+    }
+}
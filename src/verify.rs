@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use toml::{Table, Value};
+
+use crate::answer::Answer;
+
+/// Known-good answers keyed by day and part, loaded from a file like:
+///
+/// ```toml
+/// [day01]
+/// part1 = 1530215
+/// part2 = 26800609
+/// ```
+///
+/// Answers too large for a TOML integer can be written as strings.
+#[derive(Debug, Default)]
+pub struct ExpectedAnswers {
+    answers: HashMap<(u32, u32), String>,
+}
+
+impl ExpectedAnswers {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Error reading {}: {}", path.display(), e))?;
+        Self::parse(&text).map_err(|e| format!("Error in {}: {}", path.display(), e))
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let table = text.parse::<Table>().map_err(|e| e.to_string())?;
+        let mut answers = HashMap::new();
+        for (day_key, parts) in table {
+            let day = parse_key(&day_key, "day")?;
+            let parts = parts.as_table()
+                .ok_or_else(|| format!("[{}] should be a table of parts", day_key))?;
+            for (part_key, value) in parts {
+                let part = parse_key(part_key, "part")?;
+                let answer = match value {
+                    Value::Integer(number) => number.to_string(),
+                    Value::String(text) => text.clone(),
+                    _ => return Err(format!("{}.{} should be a number or a string", day_key, part_key)),
+                };
+                answers.insert((day, part), answer);
+            }
+        }
+        Ok(ExpectedAnswers { answers })
+    }
+
+    pub fn get(&self, day: u32, part: u32) -> Option<&str> {
+        self.answers.get(&(day, part)).map(|answer| answer.as_str())
+    }
+
+    pub fn check(&self, day: u32, part: u32, actual: &Answer) -> Verdict {
+        match self.get(day, part) {
+            None => Verdict::Unknown,
+            Some(expected) if expected == actual.to_string() => Verdict::Pass,
+            Some(expected) => Verdict::Fail { expected: expected.to_string() },
+        }
+    }
+}
+
+fn parse_key(key: &str, prefix: &str) -> Result<u32, String> {
+    key.strip_prefix(prefix)
+        .and_then(|number| number.parse::<u32>().ok())
+        .ok_or_else(|| format!("Unexpected key '{}', expected something like '{}1'", key, prefix))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Unknown,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "PASS"),
+            Verdict::Fail { .. } => write!(f, "FAIL"),
+            Verdict::Unknown => write!(f, "UNKNOWN"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigInt;
+
+    const ANSWERS: &str = "
+        [day01]
+        part1 = 11
+        part2 = 31
+
+        [day09]
+        part1 = \"6461289671426\"
+    ";
+
+    #[test]
+    fn it_will_parse_answers_by_day_and_part() {
+        let expected = ExpectedAnswers::parse(ANSWERS).unwrap();
+        assert_eq!(expected.get(1, 1), Some("11"));
+        assert_eq!(expected.get(1, 2), Some("31"));
+        assert_eq!(expected.get(9, 1), Some("6461289671426"));
+        assert_eq!(expected.get(9, 2), None);
+    }
+
+    #[test]
+    fn it_will_reject_unexpected_keys() {
+        assert!(ExpectedAnswers::parse("[first]\npart1 = 1").is_err());
+        assert!(ExpectedAnswers::parse("[day01]\nanswer = 1").is_err());
+        assert!(ExpectedAnswers::parse("[day01]\npart1 = 1.5").is_err());
+    }
+
+    #[test]
+    fn it_will_check_answers() {
+        let expected = ExpectedAnswers::parse(ANSWERS).unwrap();
+        assert_eq!(expected.check(1, 1, &Answer::I32(11)), Verdict::Pass);
+        assert_eq!(expected.check(1, 2, &Answer::I32(30)), Verdict::Fail { expected: String::from("31") });
+        assert_eq!(expected.check(9, 1, &Answer::BigInt(BigInt::from(6461289671426i64))), Verdict::Pass);
+        assert_eq!(expected.check(2, 1, &Answer::I32(2)), Verdict::Unknown);
+    }
+}